@@ -0,0 +1,360 @@
+use crate::auth::Claims;
+use crate::models::cases::{Case, CreateCaseRequest, SetTreatmentRequest};
+use crate::models::db::AppState;
+use crate::models::exams::{
+    ApproveDraftRequest, DraftListQuery, GenerateQuestionsRequest, RejectDraftRequest, Rubric,
+};
+use crate::models::jobs::{JobListQuery, SummarizationJob};
+use crate::models::statutes::AmendProvisionRequest;
+use crate::services::citations::normalize_citation;
+use crate::services::essays::{EssayError, set_rubric};
+use crate::services::provisions::{
+    AmendError, amend_provision, parse_provisions, replace_provisions,
+};
+use crate::services::question_drafts::{
+    DraftError, approve_draft, generate_drafts, list_drafts, reject_draft,
+};
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+};
+use serde_json::json;
+use uuid::Uuid;
+
+const JOB_STATUSES: [&str; 4] = ["pending", "running", "succeeded", "failed"];
+const DRAFT_STATUSES: [&str; 3] = ["pending", "approved", "rejected"];
+
+/// Admin-only routes; `main.rs` puts them behind `auth::require_admin`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/cases", post(create_case))
+        .route("/citations/{id}/treatment", put(set_treatment))
+        .route("/jobs", get(list_jobs))
+        .route("/questions/generate", post(generate_questions))
+        .route("/questions/drafts", get(list_question_drafts))
+        .route(
+            "/questions/drafts/{id}/approve",
+            post(approve_question_draft),
+        )
+        .route("/questions/drafts/{id}/reject", post(reject_question_draft))
+        .route("/questions/{id}/rubric", put(set_question_rubric))
+        .route("/jobs/{id}/requeue", post(requeue_job))
+        .route("/statutes/{id}/provisions", post(reparse_statute))
+        .route("/statutes/{id}/sections/{*path}", post(amend_section))
+}
+
+/// Adds a case. The citation must parse as a law report citation and is
+/// stored in canonical form; the summarization and embedding workers pick
+/// the case up from there.
+async fn create_case(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateCaseRequest>,
+) -> impl IntoResponse {
+    let citation = match normalize_citation(&payload.citation) {
+        Ok(citation) => citation,
+        Err(reason) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Invalid citation: {}", reason),
+            )
+                .into_response();
+        }
+    };
+
+    match sqlx::query_as::<_, Case>(
+        "INSERT INTO cases (title, citation, court, year, topic, difficulty, content) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) \
+         RETURNING id, title, citation, court, year, topic, difficulty, summary, content, good_law_status, created_at, updated_at",
+    )
+    .bind(&payload.title)
+    .bind(&citation)
+    .bind(&payload.court)
+    .bind(payload.year)
+    .bind(&payload.topic)
+    .bind(&payload.difficulty)
+    .bind(&payload.content)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(case) => (StatusCode::CREATED, Json(case)).into_response(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => (
+            StatusCode::CONFLICT,
+            "A case with this citation already exists",
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Corrects how a citing judgment treats the case it cites. The classifier
+/// never overwrites a treatment set here.
+async fn set_treatment(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SetTreatmentRequest>,
+) -> impl IntoResponse {
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    match sqlx::query_scalar::<_, Uuid>(
+        "UPDATE case_citations SET treatment = $1, treatment_note = $2, \
+             classified_by = 'admin', classified_at = CURRENT_TIMESTAMP \
+         WHERE id = $3 AND cited_case_id IS NOT NULL \
+         RETURNING cited_case_id",
+    )
+    .bind(payload.treatment.as_str())
+    .bind(note)
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(cited_case_id)) => Json(json!({
+            "id": id,
+            "cited_case_id": cited_case_id,
+            "treatment": payload.treatment,
+            "treatment_note": note,
+            "classified_by": "admin",
+        }))
+        .into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No case citation with that id").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Summarization jobs in one status, most recently updated first. Defaults to
+/// the failed (dead-lettered) jobs.
+async fn list_jobs(
+    State(state): State<AppState>,
+    Query(params): Query<JobListQuery>,
+) -> impl IntoResponse {
+    let status = params.status.as_deref().unwrap_or("failed");
+    if !JOB_STATUSES.contains(&status) {
+        return (StatusCode::BAD_REQUEST, "Unknown job status").into_response();
+    }
+
+    match sqlx::query_as::<_, SummarizationJob>(
+        "SELECT id, case_id, status, attempts, max_attempts, last_error, next_run_at, locked_at, created_at, updated_at \
+         FROM summarization_jobs WHERE status = $1 ORDER BY updated_at DESC LIMIT $2",
+    )
+    .bind(status)
+    .bind(params.limit.unwrap_or(50).clamp(1, 500))
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(jobs) => Json(jobs).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Gives a failed job a fresh set of attempts, starting now. The last error
+/// is kept until the next attempt replaces it.
+async fn requeue_job(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    match sqlx::query_as::<_, SummarizationJob>(
+        "UPDATE summarization_jobs SET status = 'pending', attempts = 0, next_run_at = NOW(), \
+             locked_at = NULL, updated_at = NOW() \
+         WHERE id = $1 AND status = 'failed' \
+         RETURNING id, case_id, status, attempts, max_attempts, last_error, next_run_at, locked_at, created_at, updated_at",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(job)) => Json(job).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No failed job with that id").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Re-splits a statute's text into provisions, replacing the stored ones.
+/// Use after correcting `statutes.content`.
+async fn reparse_statute(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    let content =
+        match sqlx::query_scalar::<_, String>("SELECT content FROM statutes WHERE id = $1")
+            .bind(id)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(content) => content,
+            Err(sqlx::Error::RowNotFound) => {
+                return (StatusCode::NOT_FOUND, "Statute not found").into_response();
+            }
+            Err(e) => {
+                eprintln!("Database error: {:?}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                    .into_response();
+            }
+        };
+
+    match replace_provisions(&state.db, id, &parse_provisions(&content)).await {
+        Ok(count) => Json(json!({ "provisions": count })).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Records an amendment or repeal of a provision, e.g. a constitutional
+/// alteration changing Section 36(6)(c) from a given date.
+async fn amend_section(
+    State(state): State<AppState>,
+    Path((id, path)): Path<(Uuid, String)>,
+    Json(payload): Json<AmendProvisionRequest>,
+) -> impl IntoResponse {
+    if payload.amended_by.trim().is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "The amending instrument is required",
+        )
+            .into_response();
+    }
+    if !payload.repeal && payload.text.as_deref().is_none_or(|t| t.trim().is_empty()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            "New text is required unless repealing",
+        )
+            .into_response();
+    }
+
+    let path = path.trim_matches('/').to_lowercase();
+    match amend_provision(&state.db, id, &path, &payload).await {
+        Ok(Some(version)) => (StatusCode::CREATED, Json(version)).into_response(),
+        Ok(None) => StatusCode::NO_CONTENT.into_response(),
+        Err(AmendError::NotFound) => (StatusCode::NOT_FOUND, "Provision not found").into_response(),
+        Err(AmendError::Conflict(message)) => (StatusCode::CONFLICT, message).into_response(),
+        Err(AmendError::Database(e)) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Has the AI draft exam questions from a case or course topic. The drafts
+/// wait in the moderation queue; none are published here.
+async fn generate_questions(
+    State(state): State<AppState>,
+    Json(payload): Json<GenerateQuestionsRequest>,
+) -> impl IntoResponse {
+    match generate_drafts(&state.db, &state.ai, &payload).await {
+        Ok(drafts) => (StatusCode::CREATED, Json(drafts)).into_response(),
+        Err(DraftError::NotFound) => {
+            (StatusCode::NOT_FOUND, "Case or topic not found").into_response()
+        }
+        Err(e) => draft_error(e),
+    }
+}
+
+/// The moderation queue. Defaults to pending drafts, oldest first.
+async fn list_question_drafts(
+    State(state): State<AppState>,
+    Query(params): Query<DraftListQuery>,
+) -> impl IntoResponse {
+    let status = params.status.as_deref().unwrap_or("pending");
+    if !DRAFT_STATUSES.contains(&status) {
+        return (StatusCode::BAD_REQUEST, "Unknown draft status").into_response();
+    }
+
+    match list_drafts(&state.db, status, params.limit.unwrap_or(50).clamp(1, 500)).await {
+        Ok(drafts) => Json(drafts).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Publishes a draft to the question bank, with any corrections in the body.
+async fn approve_question_draft(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ApproveDraftRequest>,
+) -> impl IntoResponse {
+    let Some(reviewer) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match approve_draft(&state.db, id, reviewer, &payload).await {
+        Ok(draft) => Json(draft).into_response(),
+        Err(e) => draft_error(e),
+    }
+}
+
+async fn reject_question_draft(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RejectDraftRequest>,
+) -> impl IntoResponse {
+    let Some(reviewer) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+
+    match reject_draft(&state.db, id, reviewer, note).await {
+        Ok(draft) => Json(draft).into_response(),
+        Err(e) => draft_error(e),
+    }
+}
+
+fn draft_error(e: DraftError) -> Response {
+    match e {
+        DraftError::NotFound => (StatusCode::NOT_FOUND, "Draft not found").into_response(),
+        DraftError::Invalid(message) => (StatusCode::UNPROCESSABLE_ENTITY, message).into_response(),
+        DraftError::Conflict(message) => (StatusCode::CONFLICT, message).into_response(),
+        DraftError::Ai(message) => {
+            eprintln!("AI error: {}", message);
+            (StatusCode::BAD_GATEWAY, "Failed to generate questions").into_response()
+        }
+        DraftError::Database(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Sets the marking rubric of an essay question. Students can only have
+/// their answers marked once a question has one.
+async fn set_question_rubric(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<Rubric>,
+) -> impl IntoResponse {
+    match set_rubric(&state.db, id, payload).await {
+        Ok(rubric) => Json(rubric).into_response(),
+        Err(EssayError::NotFound) => (StatusCode::NOT_FOUND, "Question not found").into_response(),
+        Err(EssayError::Invalid(message)) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, message).into_response()
+        }
+        Err(EssayError::Ai(message)) => {
+            eprintln!("AI error: {}", message);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+        Err(EssayError::Database(e)) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
@@ -1,25 +1,39 @@
-use crate::models::cases::{Case, CaseSearchQuery};
+use crate::models::cases::{
+    Case, CaseBrief, CaseDetail, CaseSearchQuery, CaseSearchResult, CitationLookupQuery,
+    CitedAuthority, CitingCase, SemanticCaseResult, SemanticSearchQuery,
+};
 use crate::models::db::AppState;
+use crate::services::ai::EmbeddingTask;
+use crate::services::citations::{citation_key, normalize_citation};
 use axum::{
     Json, Router,
     extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     routing::get,
 };
+use pgvector::Vector;
+use sqlx::{Postgres, QueryBuilder};
 use uuid::Uuid;
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(list_cases))
+        .route("/by-citation", get(find_by_citation))
         .route("/{id}", get(get_case))
+        .route("/{id}/citations", get(get_citations))
+        .route("/{id}/cited-by", get(get_cited_by))
 }
 
 async fn list_cases(
     State(state): State<AppState>,
     Query(params): Query<CaseSearchQuery>,
 ) -> impl IntoResponse {
-    let mut query = "SELECT id, title, citation, court, year, topic, difficulty, summary, content, created_at, updated_at FROM cases".to_string();
+    if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        return search_cases(&state, q, &params).await;
+    }
+
+    let mut query = "SELECT id, title, citation, court, year, topic, difficulty, summary, content, good_law_status, created_at, updated_at FROM cases".to_string();
     let mut conditions = Vec::new();
 
     if let Some(topic) = &params.topic {
@@ -46,19 +60,213 @@ async fn list_cases(
     }
 }
 
+/// Ranked full-text search over title, citation, court, summary and content.
+/// `q` accepts web-search syntax: quoted phrases, `or`, and `-` exclusions.
+async fn search_cases(state: &AppState, q: &str, params: &CaseSearchQuery) -> Response {
+    let mut query = QueryBuilder::<Postgres>::new(
+        "SELECT id, title, citation, court, year, topic, difficulty, summary, content, good_law_status, created_at, updated_at, \
+         ts_rank_cd(search_vector, query) AS rank, \
+         ts_headline('english', coalesce(summary, '') || ' ' || coalesce(content, ''), query, \
+             'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=35, MinWords=15') AS snippet \
+         FROM cases, websearch_to_tsquery('english', ",
+    );
+    query.push_bind(q);
+    query.push(") AS query WHERE search_vector @@ query");
+
+    if let Some(topic) = &params.topic {
+        query.push(" AND topic = ");
+        query.push_bind(topic);
+    }
+
+    query.push(" ORDER BY rank DESC, year DESC LIMIT ");
+    query.push_bind(params.limit.unwrap_or(20).clamp(1, 100));
+
+    match query
+        .build_query_as::<CaseSearchResult>()
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(results) => Json(results).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Nearest cases to a plain-language description of a fact pattern,
+/// ranked by cosine distance between embeddings. Summary-only cases are
+/// embedded too, so results can have null `content`. Routed from `main.rs` so it
+/// can sit behind the rate limiter.
+pub async fn semantic_search(
+    State(state): State<AppState>,
+    Query(params): Query<SemanticSearchQuery>,
+) -> impl IntoResponse {
+    let q = params.q.trim();
+    if q.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Query must not be empty").into_response();
+    }
+
+    let embedding = match state.ai.embed_text(q, EmbeddingTask::Query).await {
+        Ok(values) => Vector::from(values),
+        Err(e) => {
+            eprintln!("Embedding error: {:?}", e);
+            return (StatusCode::BAD_GATEWAY, "Failed to embed query").into_response();
+        }
+    };
+
+    match sqlx::query_as::<_, SemanticCaseResult>(
+        "SELECT id, title, citation, court, year, topic, difficulty, summary, content, good_law_status, created_at, updated_at, \
+         embedding <=> $1 AS distance \
+         FROM cases WHERE embedding IS NOT NULL ORDER BY embedding <=> $1 LIMIT $2",
+    )
+    .bind(embedding)
+    .bind(params.limit.unwrap_or(10).clamp(1, 50))
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(results) => Json(results).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
 async fn get_case(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
-    match sqlx::query_as::<_, Case>(
-        "SELECT id, title, citation, court, year, topic, difficulty, summary, content, created_at, updated_at FROM cases WHERE id = $1"
+    let case = match sqlx::query_as::<_, Case>(
+        "SELECT id, title, citation, court, year, topic, difficulty, summary, content, good_law_status, created_at, updated_at FROM cases WHERE id = $1"
     )
     .bind(id)
     .fetch_one(&state.db)
     .await
     {
-        Ok(case) => Json(case).into_response(),
-        Err(sqlx::Error::RowNotFound) => (StatusCode::NOT_FOUND, "Case not found").into_response(),
+        Ok(case) => case,
+        Err(sqlx::Error::RowNotFound) => {
+            return (StatusCode::NOT_FOUND, "Case not found").into_response();
+        }
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
+    };
+
+    match sqlx::query_as::<_, CaseBrief>(
+        "SELECT facts, issues, holding, ratio_decidendi, obiter_dicta, disposition, judges \
+         FROM case_briefs WHERE case_id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(brief) => Json(CaseDetail { case, brief }).into_response(),
         Err(e) => {
             eprintln!("Database error: {:?}", e);
             (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
         }
     }
 }
+
+/// Looks a case up by its law report citation, however it was typed:
+/// "1996 9 nwlr pt 475 710" finds "(1996) 9 NWLR (Pt. 475) 710".
+async fn find_by_citation(
+    State(state): State<AppState>,
+    Query(params): Query<CitationLookupQuery>,
+) -> impl IntoResponse {
+    let c = params.c.trim();
+    if c.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Citation must not be empty").into_response();
+    }
+
+    // Citations in series the parser does not know are still matched on
+    // their letters and digits.
+    let key = citation_key(&normalize_citation(c).unwrap_or_else(|_| c.to_string()));
+
+    match sqlx::query_as::<_, Case>(
+        "SELECT id, title, citation, court, year, topic, difficulty, summary, content, good_law_status, created_at, updated_at \
+         FROM cases WHERE citation_key = $1",
+    )
+    .bind(key)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(case)) => Json(case).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No case with that citation").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// The cases and statutes a judgment cites, in the order they are first
+/// mentioned.
+async fn get_citations(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    if let Err(response) = ensure_case_exists(&state, id).await {
+        return response;
+    }
+
+    match sqlx::query_as::<_, CitedAuthority>(
+        "SELECT cc.id, cc.cited_text, cc.context, \
+                c.id AS cited_case_id, c.title AS cited_case_title, \
+                c.citation AS cited_case_citation, c.year AS cited_case_year, \
+                s.id AS cited_statute_id, s.title AS cited_statute_title, \
+                cc.treatment, cc.treatment_note \
+         FROM case_citations cc \
+         LEFT JOIN cases c ON c.id = cc.cited_case_id \
+         LEFT JOIN statutes s ON s.id = cc.cited_statute_id \
+         WHERE cc.citing_case_id = $1 \
+         ORDER BY cc.position",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(citations) => Json(citations).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Later judgments that cite this case, newest first.
+async fn get_cited_by(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    if let Err(response) = ensure_case_exists(&state, id).await {
+        return response;
+    }
+
+    match sqlx::query_as::<_, CitingCase>(
+        "SELECT cc.id AS citation_id, c.id AS case_id, c.title, c.citation, c.court, c.year, cc.context, \
+                cc.treatment, cc.treatment_note \
+         FROM case_citations cc \
+         JOIN cases c ON c.id = cc.citing_case_id \
+         WHERE cc.cited_case_id = $1 \
+         ORDER BY c.year DESC, c.title",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(cases) => Json(cases).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn ensure_case_exists(state: &AppState, id: Uuid) -> Result<(), Response> {
+    match sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM cases WHERE id = $1)")
+        .bind(id)
+        .fetch_one(&state.db)
+        .await
+    {
+        Ok(true) => Ok(()),
+        Ok(false) => Err((StatusCode::NOT_FOUND, "Case not found").into_response()),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response())
+        }
+    }
+}
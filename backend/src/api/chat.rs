@@ -1,47 +1,454 @@
+use crate::auth::Claims;
+use crate::models::chat::{ChatHistoryPage, ChatHistoryQuery, ChatMessage};
 use crate::models::db::AppState;
-use crate::services::ai::AiService;
-use axum::{Json, Router, extract::State, response::IntoResponse, routing::post};
+use crate::rate_limit::{UsageSubject, record_tokens};
+use crate::services::ai::{ChatRole, ChatTurn};
+use crate::services::retrieval::{Source, retrieve_sources};
+use crate::services::text::estimate_tokens;
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+    routing::{delete, get, post},
+};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+// How many earlier messages are replayed to the model as context.
+const HISTORY_LIMIT: i64 = 20;
+// Far past any real history; keeps the OFFSET arithmetic from overflowing.
+const MAX_HISTORY_PAGE: i64 = 100_000;
+// Shown when the model call fails. The provider's own error stays in the
+// logs, and is never saved as a reply that later turns would replay.
+const LEXA_UNAVAILABLE: &str = "LEXA could not answer just now. Please try again.";
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/", post(chat_with_lexa))
+    Router::new()
+        .route("/history", get(chat_history))
+        .route("/messages/{id}", delete(delete_message))
+}
+
+/// The routes that call LEXA; `main.rs` puts them behind the rate limiter.
+pub fn lexa_router() -> Router<AppState> {
+    Router::new()
+        .route("/", post(chat_with_lexa))
+        .route("/stream", post(stream_chat_with_lexa))
 }
 
 #[derive(Deserialize)]
 pub struct ChatRequest {
     pub message: String,
+    /// Continue an existing conversation; a new one is started when omitted.
+    pub conversation_id: Option<Uuid>,
 }
 
 #[derive(Serialize)]
 pub struct ChatResponse {
+    pub conversation_id: Uuid,
     pub response: String,
+    /// Library cases and statutes given to LEXA as context for this answer.
+    pub sources: Vec<Source>,
+}
+
+#[derive(Serialize)]
+struct StreamToken<'a> {
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct StreamConversation {
+    conversation_id: Uuid,
 }
 
 pub async fn chat_with_lexa(
     State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Extension(usage): Extension<UsageSubject>,
     Json(payload): Json<ChatRequest>,
 ) -> impl IntoResponse {
-    let ai_service = AiService::new();
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
 
-    let response_text = match ai_service.ask_lexa(&payload.message).await {
-        Ok(res) => res,
-        Err(e) => format!("LEXA is currently troubleshooting a connection: {}", e),
+    let (conversation_id, history) = match resolve_conversation(
+        &state.db,
+        user_id,
+        payload.conversation_id,
+        &payload.message,
+    )
+    .await
+    {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
     };
 
-    // Persistence attempt (Best effort, don't fail the request if DB save fails)
-    let _ = sqlx::query("INSERT INTO chat_messages (role, content) VALUES ($1, $2)")
-        .bind("user")
-        .bind(&payload.message)
-        .execute(&state.db)
-        .await;
+    let sources = retrieve_sources(&state.db, &state.ai, &payload.message).await;
 
-    let _ = sqlx::query("INSERT INTO chat_messages (role, content) VALUES ($1, $2)")
-        .bind("assistant")
-        .bind(&response_text)
-        .execute(&state.db)
-        .await;
+    let response_text = match state
+        .ai
+        .ask_lexa(&payload.message, &history, &sources)
+        .await
+    {
+        Ok(res) => res,
+        Err(e) => {
+            eprintln!("LEXA request failed: {}", e);
+            // The question is kept so the conversation reads as the user wrote it.
+            save_exchange(&state.db, user_id, conversation_id, &payload.message, "").await;
+            record_tokens(
+                &state.db,
+                &usage,
+                exchange_tokens(&payload.message, &history, &sources, ""),
+            )
+            .await;
+            return (StatusCode::BAD_GATEWAY, LEXA_UNAVAILABLE).into_response();
+        }
+    };
+
+    save_exchange(
+        &state.db,
+        user_id,
+        conversation_id,
+        &payload.message,
+        &response_text,
+    )
+    .await;
+    record_tokens(
+        &state.db,
+        &usage,
+        exchange_tokens(&payload.message, &history, &sources, &response_text),
+    )
+    .await;
 
     Json(ChatResponse {
+        conversation_id,
         response: response_text,
+        sources,
     })
+    .into_response()
+}
+
+/// Streams LEXA's answer as Server-Sent Events:
+/// - `conversation`: `{"conversation_id": "..."}`, sent first
+/// - `sources`: the retrieved library sources, sent once before any text
+/// - `token`: `{"text": "..."}` for each fragment of the answer
+/// - `error`: a message if generation fails part way
+/// - `done`: sent last, once the exchange has been saved
+///
+/// Generation runs in its own task, so the full answer is still saved to
+/// `chat_messages` if the client disconnects early.
+pub async fn stream_chat_with_lexa(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Extension(usage): Extension<UsageSubject>,
+    Json(payload): Json<ChatRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    let (conversation_id, history) = match resolve_conversation(
+        &state.db,
+        user_id,
+        payload.conversation_id,
+        &payload.message,
+    )
+    .await
+    {
+        Ok(resolved) => resolved,
+        Err(response) => return response,
+    };
+
+    let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(64);
+
+    tokio::spawn(async move {
+        if let Ok(event) = Event::default()
+            .event("conversation")
+            .json_data(StreamConversation { conversation_id })
+        {
+            let _ = tx.send(Ok(event)).await;
+        }
+
+        let sources = retrieve_sources(&state.db, &state.ai, &payload.message).await;
+
+        if let Ok(event) = Event::default().event("sources").json_data(&sources) {
+            let _ = tx.send(Ok(event)).await;
+        }
+
+        let mut response_text = String::new();
+        match state
+            .ai
+            .stream_lexa(&payload.message, &history, &sources)
+            .await
+        {
+            Ok(mut stream) => {
+                while let Some(chunk) = stream.next().await {
+                    match chunk {
+                        Ok(text) => {
+                            if let Ok(event) = Event::default()
+                                .event("token")
+                                .json_data(StreamToken { text: &text })
+                            {
+                                let _ = tx.send(Ok(event)).await;
+                            }
+                            response_text.push_str(&text);
+                        }
+                        Err(e) => {
+                            eprintln!("LEXA stream interrupted: {}", e);
+                            let _ = tx
+                                .send(Ok(Event::default()
+                                    .event("error")
+                                    .data("LEXA lost the connection mid-answer")))
+                                .await;
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("LEXA request failed: {}", e);
+                let _ = tx
+                    .send(Ok(Event::default().event("error").data(LEXA_UNAVAILABLE)))
+                    .await;
+            }
+        }
+
+        save_exchange(
+            &state.db,
+            user_id,
+            conversation_id,
+            &payload.message,
+            &response_text,
+        )
+        .await;
+        record_tokens(
+            &state.db,
+            &usage,
+            exchange_tokens(&payload.message, &history, &sources, &response_text),
+        )
+        .await;
+        let _ = tx.send(Ok(Event::default().event("done").data(""))).await;
+    });
+
+    Sse::new(ReceiverStream::new(rx))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// The signed-in user's messages across all conversations, newest first.
+async fn chat_history(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<ChatHistoryQuery>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    let page = params.page.unwrap_or(1).clamp(1, MAX_HISTORY_PAGE);
+    let per_page = params.per_page.unwrap_or(50).clamp(1, 100);
+
+    let total =
+        match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM chat_messages WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(total) => total,
+            Err(e) => {
+                eprintln!("Database error: {:?}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                    .into_response();
+            }
+        };
+
+    match sqlx::query_as::<_, ChatMessage>(
+        "SELECT id, conversation_id, user_id, role, content, created_at FROM chat_messages \
+         WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+    )
+    .bind(user_id)
+    .bind(per_page)
+    .bind((page - 1) * per_page)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(messages) => Json(ChatHistoryPage {
+            messages,
+            page,
+            per_page,
+            total,
+        })
+        .into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn delete_message(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match sqlx::query("DELETE FROM chat_messages WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+    {
+        Ok(result) if result.rows_affected() == 0 => {
+            (StatusCode::NOT_FOUND, "Message not found").into_response()
+        }
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Loads the recent history of one of the user's conversations, or starts a
+/// new one titled after the opening message. Other users' conversations are
+/// reported as not found.
+async fn resolve_conversation(
+    db: &PgPool,
+    user_id: Uuid,
+    conversation_id: Option<Uuid>,
+    message: &str,
+) -> Result<(Uuid, Vec<ChatTurn>), Response> {
+    let Some(conversation_id) = conversation_id else {
+        let title: String = message.trim().chars().take(60).collect();
+        let title = if title.is_empty() {
+            "New conversation".to_string()
+        } else {
+            title
+        };
+
+        return match sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO conversations (user_id, title) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(user_id)
+        .bind(title)
+        .fetch_one(db)
+        .await
+        {
+            Ok(id) => Ok((id, Vec::new())),
+            Err(e) => {
+                eprintln!("Database error: {:?}", e);
+                Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response())
+            }
+        };
+    };
+
+    match sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM conversations WHERE id = $1 AND user_id = $2",
+    )
+    .bind(conversation_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return Err((StatusCode::NOT_FOUND, "Conversation not found").into_response());
+        }
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            return Err(
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+            );
+        }
+    }
+
+    // Provider errors saved as replies before they were kept out of the
+    // history are not replayed either.
+    match sqlx::query_as::<_, ChatMessage>(
+        "SELECT id, conversation_id, user_id, role, content, created_at FROM ( \
+             SELECT id, conversation_id, user_id, role, content, created_at FROM chat_messages \
+             WHERE conversation_id = $1 \
+               AND NOT (role <> 'user' AND content LIKE 'LEXA is currently troubleshooting a connection:%') \
+             ORDER BY created_at DESC LIMIT $2 \
+         ) recent ORDER BY created_at ASC",
+    )
+    .bind(conversation_id)
+    .bind(HISTORY_LIMIT)
+    .fetch_all(db)
+    .await
+    {
+        Ok(messages) => Ok((
+            conversation_id,
+            messages
+                .into_iter()
+                .map(|m| ChatTurn {
+                    role: ChatRole::from_db(&m.role),
+                    content: m.content,
+                })
+                .collect(),
+        )),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response())
+        }
+    }
+}
+
+/// Approximate tokens spent on one exchange: the prompt (question, history
+/// and sources) plus the reply.
+fn exchange_tokens(message: &str, history: &[ChatTurn], sources: &[Source], response: &str) -> i64 {
+    let prompt = estimate_tokens(message)
+        + history
+            .iter()
+            .map(|t| estimate_tokens(&t.content))
+            .sum::<usize>()
+        + sources
+            .iter()
+            .map(|s| estimate_tokens(&s.excerpt))
+            .sum::<usize>();
+    (prompt + estimate_tokens(response)) as i64
+}
+
+// Persistence is best effort: a failed save must not fail the chat itself.
+async fn save_exchange(
+    db: &PgPool,
+    user_id: Uuid,
+    conversation_id: Uuid,
+    message: &str,
+    response: &str,
+) {
+    for (role, content) in [("user", message), ("assistant", response)] {
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Err(e) = sqlx::query(
+            "INSERT INTO chat_messages (user_id, conversation_id, role, content) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(user_id)
+        .bind(conversation_id)
+        .bind(role)
+        .bind(content)
+        .execute(db)
+        .await
+        {
+            eprintln!("Failed to save {} chat message: {:?}", role, e);
+        }
+    }
+
+    let _ = sqlx::query("UPDATE conversations SET updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+        .bind(conversation_id)
+        .execute(db)
+        .await;
 }
@@ -0,0 +1,185 @@
+use crate::auth::Claims;
+use crate::models::chat::{
+    ChatMessage, Conversation, CreateConversationRequest, RenameConversationRequest,
+};
+use crate::models::db::AppState;
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, patch},
+};
+use uuid::Uuid;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_conversations).post(create_conversation))
+        .route(
+            "/{id}",
+            patch(rename_conversation).delete(delete_conversation),
+        )
+        .route("/{id}/messages", get(conversation_messages))
+}
+
+async fn list_conversations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match sqlx::query_as::<_, Conversation>(
+        "SELECT id, user_id, title, created_at, updated_at FROM conversations \
+         WHERE user_id = $1 ORDER BY updated_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(conversations) => Json(conversations).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn create_conversation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateConversationRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    let title = payload
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("New conversation");
+
+    match sqlx::query_as::<_, Conversation>(
+        "INSERT INTO conversations (user_id, title) VALUES ($1, $2) \
+         RETURNING id, user_id, title, created_at, updated_at",
+    )
+    .bind(user_id)
+    .bind(title)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(conversation) => (StatusCode::CREATED, Json(conversation)).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn rename_conversation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RenameConversationRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    let title = payload.title.trim();
+    if title.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Title must not be empty").into_response();
+    }
+
+    match sqlx::query_as::<_, Conversation>(
+        "UPDATE conversations SET title = $1, updated_at = CURRENT_TIMESTAMP \
+         WHERE id = $2 AND user_id = $3 \
+         RETURNING id, user_id, title, created_at, updated_at",
+    )
+    .bind(title)
+    .bind(id)
+    .bind(user_id)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(conversation) => Json(conversation).into_response(),
+        Err(sqlx::Error::RowNotFound) => {
+            (StatusCode::NOT_FOUND, "Conversation not found").into_response()
+        }
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn delete_conversation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match sqlx::query("DELETE FROM conversations WHERE id = $1 AND user_id = $2")
+        .bind(id)
+        .bind(user_id)
+        .execute(&state.db)
+        .await
+    {
+        Ok(result) if result.rows_affected() == 0 => {
+            (StatusCode::NOT_FOUND, "Conversation not found").into_response()
+        }
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Every message in one of the user's conversations, oldest first.
+async fn conversation_messages(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM conversations WHERE id = $1 AND user_id = $2",
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, "Conversation not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
+    }
+
+    match sqlx::query_as::<_, ChatMessage>(
+        "SELECT id, conversation_id, user_id, role, content, created_at FROM chat_messages \
+         WHERE conversation_id = $1 ORDER BY created_at ASC",
+    )
+    .bind(id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(messages) => Json(messages).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
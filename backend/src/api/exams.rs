@@ -0,0 +1,194 @@
+use crate::auth::Claims;
+use crate::models::db::AppState;
+use crate::models::exams::{
+    AnswerKey, AnswerResult, ExamQuestion, ExamQuestionQuery, SubmitAnswerRequest,
+    SubmitEssayRequest,
+};
+use crate::services::essays::{EssayError, list_attempts, mark_essay};
+use crate::services::exams::is_correct;
+use crate::services::review::enqueue_missed_question;
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_questions))
+        .route("/{id}", get(get_question))
+}
+
+/// Questions filtered by category, difficulty, year and source (e.g.
+/// `?category=Constitutional Law&source=Bar Finals`), without their answers.
+async fn list_questions(
+    State(state): State<AppState>,
+    Query(params): Query<ExamQuestionQuery>,
+) -> impl IntoResponse {
+    let mut query = QueryBuilder::<Postgres>::new(
+        "SELECT id, question, question_type, options, law_category, difficulty, source, year, created_at \
+         FROM exam_questions WHERE TRUE",
+    );
+
+    if let Some(category) = params.category.as_deref().filter(|c| !c.is_empty()) {
+        query.push(" AND lower(law_category) = lower(");
+        query.push_bind(category.to_string());
+        query.push(")");
+    }
+    if let Some(difficulty) = params.difficulty.as_deref().filter(|d| !d.is_empty()) {
+        query.push(" AND lower(difficulty) = lower(");
+        query.push_bind(difficulty.to_string());
+        query.push(")");
+    }
+    if let Some(source) = params.source.as_deref().filter(|s| !s.is_empty()) {
+        query.push(" AND lower(source) = lower(");
+        query.push_bind(source.to_string());
+        query.push(")");
+    }
+    if let Some(question_type) = params.question_type.as_deref().filter(|t| !t.is_empty()) {
+        query.push(" AND question_type = ");
+        query.push_bind(question_type.to_string());
+    }
+    if let Some(year) = params.year {
+        query.push(" AND year = ");
+        query.push_bind(year);
+    }
+
+    query.push(" ORDER BY law_category, year DESC NULLS LAST, created_at LIMIT ");
+    query.push_bind(params.limit.unwrap_or(50).clamp(1, 200));
+    query.push(" OFFSET ");
+    query.push_bind(params.offset.unwrap_or(0).max(0));
+
+    match query
+        .build_query_as::<ExamQuestion>()
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(questions) => Json(questions).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn get_question(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    match sqlx::query_as::<_, ExamQuestion>(
+        "SELECT id, question, question_type, options, law_category, difficulty, source, year, created_at \
+         FROM exam_questions WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(question)) => Json(question).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Question not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Marks a signed-in user's answer and only then reveals the correct answer
+/// and explanation. A wrong answer queues the question for review. Routed
+/// from `main.rs` so it sits behind `require_auth`.
+pub async fn submit_answer(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SubmitAnswerRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+    if payload.answer.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "Answer must not be empty").into_response();
+    }
+
+    match sqlx::query_as::<_, AnswerKey>(
+        "SELECT question_type, options, correct_answer, explanation FROM exam_questions WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(Some(key)) if key.question_type == "essay" => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Essay questions are marked at /api/exams/{id}/essay",
+        )
+            .into_response(),
+        Ok(Some(key)) => {
+            let correct = is_correct(&key, &payload.answer);
+            if !correct && let Err(e) = enqueue_missed_question(&state.db, user_id, id).await {
+                eprintln!("Failed to queue question {} for review: {:?}", id, e);
+            }
+
+            Json(AnswerResult {
+                question_id: id,
+                correct,
+                correct_answer: key.correct_answer,
+                explanation: key.explanation,
+            })
+            .into_response()
+        }
+        Ok(None) => (StatusCode::NOT_FOUND, "Question not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Marks a signed-in user's essay answer against the question's rubric and
+/// reveals the model answer. Routed from `main.rs` behind `require_auth` and
+/// the rate limit, since each call goes to the AI.
+pub async fn submit_essay(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SubmitEssayRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match mark_essay(&state.db, &state.ai, user_id, id, &payload.answer).await {
+        Ok(result) => Json(result).into_response(),
+        Err(EssayError::NotFound) => (StatusCode::NOT_FOUND, "Question not found").into_response(),
+        Err(EssayError::Invalid(message)) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, message).into_response()
+        }
+        Err(EssayError::Ai(message)) => {
+            eprintln!("AI error: {}", message);
+            (StatusCode::BAD_GATEWAY, "Failed to mark the answer").into_response()
+        }
+        Err(EssayError::Database(e)) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// The signed-in user's earlier attempts at an essay question, newest first.
+pub async fn list_essay_attempts(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match list_attempts(&state.db, user_id, id, 50).await {
+        Ok(attempts) => Json(attempts).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
@@ -1,4 +1,10 @@
+pub mod admin;
 pub mod auth;
 pub mod cases;
 pub mod chat;
+pub mod conversations;
 pub mod courses;
+pub mod exams;
+pub mod practice;
+pub mod review;
+pub mod statutes;
@@ -0,0 +1,158 @@
+use crate::auth::Claims;
+use crate::models::db::AppState;
+use crate::models::practice::{SaveAnswerRequest, SessionListQuery, StartSessionRequest};
+use crate::services::practice::{
+    PracticeError, grade_session, list_sessions, load_session, save_answer, start_session,
+};
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+};
+use uuid::Uuid;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_practice_sessions).post(start_practice_session),
+        )
+        .route("/{id}", get(get_practice_session))
+        .route("/{id}/answers", put(save_practice_answer))
+        .route("/{id}/submit", post(submit_practice_session))
+}
+
+async fn list_practice_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<SessionListQuery>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match list_sessions(&state.db, user_id, params.limit.unwrap_or(20).clamp(1, 100)).await {
+        Ok(sessions) => Json(sessions).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Starts a session and returns it with its questions, answers hidden.
+async fn start_practice_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<StartSessionRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match start_session(&state.db, user_id, &payload).await {
+        Ok(id) => match load_session(&state.db, user_id, id).await {
+            Ok(Some(session)) => (StatusCode::CREATED, Json(session)).into_response(),
+            Ok(None) => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+            Err(e) => {
+                eprintln!("Database error: {:?}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+            }
+        },
+        Err(PracticeError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            "No questions match the requested categories and filters",
+        )
+            .into_response(),
+        Err(e) => practice_error(e),
+    }
+}
+
+async fn get_practice_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match load_session(&state.db, user_id, id).await {
+        Ok(Some(session)) => Json(session).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Saves or changes one answer. Refused once the session is submitted or
+/// its time is up.
+async fn save_practice_answer(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SaveAnswerRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+    if payload.answer.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, "Answer must not be empty").into_response();
+    }
+
+    match save_answer(&state.db, user_id, id, payload.question_id, &payload.answer).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => practice_error(e),
+    }
+}
+
+/// Grades the session and returns the marked answers with the score per
+/// category.
+async fn submit_practice_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    // Loading first checks the session belongs to this user.
+    match load_session(&state.db, user_id, id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
+    }
+
+    let graded = match grade_session(&state.db, id).await {
+        Ok(()) => load_session(&state.db, user_id, id).await,
+        Err(e) => Err(e),
+    };
+    match graded {
+        Ok(Some(session)) => Json(session).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+fn practice_error(e: PracticeError) -> Response {
+    match e {
+        PracticeError::NotFound => (StatusCode::NOT_FOUND, "Session not found").into_response(),
+        PracticeError::Invalid(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+        PracticeError::Conflict(message) => (StatusCode::CONFLICT, message).into_response(),
+        PracticeError::Database(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
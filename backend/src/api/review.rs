@@ -0,0 +1,153 @@
+use crate::auth::Claims;
+use crate::models::db::AppState;
+use crate::models::exams::AnswerResult;
+use crate::models::review::{DueQuery, ReviewOutcome, ReviewRequest};
+use crate::services::exams::is_correct;
+use crate::services::review::{due_items, flag_case, question_key, record_review, remove_item};
+use axum::{
+    Extension, Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use uuid::Uuid;
+
+// SM-2 grades given to a review answered through `answer` alone.
+const CORRECT_QUALITY: u8 = 4;
+const WRONG_QUALITY: u8 = 1;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/due", get(get_due))
+        .route("/cases/{id}", post(flag_case_for_review))
+        .route("/{id}", post(review_item).delete(delete_review_item))
+}
+
+/// Today's review queue: missed questions (answers hidden) and flagged
+/// cases that are due.
+async fn get_due(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<DueQuery>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match due_items(&state.db, user_id, params.limit.unwrap_or(50).clamp(1, 200)).await {
+        Ok(items) => Json(items).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn flag_case_for_review(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match flag_case(&state.db, user_id, id).await {
+        Ok(Some(item)) => (StatusCode::CREATED, Json(item)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Case not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Records a review and schedules the next one. A question can be answered
+/// here, and the answer is marked and revealed.
+async fn review_item(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReviewRequest>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+    if payload.quality.is_some_and(|q| q > 5) {
+        return (StatusCode::BAD_REQUEST, "Quality must be between 0 and 5").into_response();
+    }
+    let answer = payload
+        .answer
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty());
+
+    let mut marked = None;
+    if let Some(answer) = answer {
+        match question_key(&state.db, user_id, id).await {
+            Ok(Some((question_id, key))) => {
+                marked = Some(AnswerResult {
+                    question_id,
+                    correct: is_correct(&key, answer),
+                    correct_answer: key.correct_answer,
+                    explanation: key.explanation,
+                });
+            }
+            Ok(None) => {
+                return (StatusCode::NOT_FOUND, "No review question with that id").into_response();
+            }
+            Err(e) => {
+                eprintln!("Database error: {:?}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                    .into_response();
+            }
+        }
+    }
+
+    let quality = match (payload.quality, &marked) {
+        (Some(quality), _) => quality,
+        (None, Some(result)) => {
+            if result.correct {
+                CORRECT_QUALITY
+            } else {
+                WRONG_QUALITY
+            }
+        }
+        (None, None) => {
+            return (StatusCode::BAD_REQUEST, "Send a quality or an answer").into_response();
+        }
+    };
+
+    match record_review(&state.db, user_id, id, quality).await {
+        Ok(Some(item)) => Json(ReviewOutcome {
+            item,
+            answer: marked,
+        })
+        .into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Review item not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn delete_review_item(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(user_id) = claims.user_id() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID in claims").into_response();
+    };
+
+    match remove_item(&state.db, user_id, id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, "Review item not found").into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
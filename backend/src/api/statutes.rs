@@ -0,0 +1,250 @@
+use crate::models::db::AppState;
+use crate::models::statutes::{
+    AsOfQuery, ProvisionVersion, Statute, StatuteListQuery, StatuteProvision, StatuteSearchQuery,
+    StatuteSearchResult, StatuteSummary,
+};
+use crate::services::provisions::provision_tree;
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+};
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+// Provisions in force on $2 (defaulting to today), each with the text of the
+// version in force then. `{from}` is the table or CTE the rows come from.
+const PROVISIONS_AS_OF: &str = "SELECT p.id, p.parent_id, p.kind, p.number, \
+         coalesce(v.heading, p.heading) AS heading, coalesce(v.text, p.text) AS text, p.path, \
+         coalesce(v.effective_from, p.effective_from) AS effective_from, \
+         coalesce(v.effective_to, p.effective_to) AS effective_to, v.amended_by \
+     FROM {from} p \
+     LEFT JOIN LATERAL ( \
+         SELECT heading, text, effective_from, effective_to, amended_by FROM statute_provision_versions \
+         WHERE provision_id = p.id \
+           AND (effective_from IS NULL OR effective_from <= coalesce($2, CURRENT_DATE)) \
+           AND (effective_to IS NULL OR effective_to > coalesce($2, CURRENT_DATE)) \
+         ORDER BY effective_from DESC NULLS LAST LIMIT 1 \
+     ) v ON true \
+     WHERE p.statute_id = $1 \
+       AND (p.effective_from IS NULL OR p.effective_from <= coalesce($2, CURRENT_DATE)) \
+       AND (p.effective_to IS NULL OR p.effective_to > coalesce($2, CURRENT_DATE)) \
+     ORDER BY p.position";
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_statutes))
+        .route("/search", get(search_statutes))
+        .route("/{id}", get(get_statute))
+        .route("/{id}/provisions", get(list_provisions))
+        .route("/{id}/sections/{*path}", get(get_section))
+        .route("/{id}/history/{*path}", get(get_section_history))
+}
+
+/// Statutes by title, optionally filtered by `category` (case-insensitive).
+async fn list_statutes(
+    State(state): State<AppState>,
+    Query(params): Query<StatuteListQuery>,
+) -> impl IntoResponse {
+    let mut query =
+        QueryBuilder::<Postgres>::new("SELECT id, title, year, category, created_at FROM statutes");
+
+    if let Some(category) = &params.category {
+        query.push(" WHERE lower(category) = lower(");
+        query.push_bind(category);
+        query.push(")");
+    }
+
+    query.push(" ORDER BY title, year");
+
+    if let Some(limit) = params.limit {
+        query.push(" LIMIT ");
+        query.push_bind(limit.clamp(1, 100));
+    }
+
+    match query
+        .build_query_as::<StatuteSummary>()
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(statutes) => Json(statutes).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// Ranked full-text search over statute titles and text, with the same
+/// web-search syntax as case search.
+async fn search_statutes(
+    State(state): State<AppState>,
+    Query(params): Query<StatuteSearchQuery>,
+) -> impl IntoResponse {
+    let q = params.q.trim();
+    if q.is_empty() {
+        return (StatusCode::BAD_REQUEST, "Query must not be empty").into_response();
+    }
+
+    let mut query = QueryBuilder::<Postgres>::new(
+        "SELECT id, title, year, category, created_at, \
+         ts_rank_cd(search_vector, query) AS rank, \
+         ts_headline('english', content, query, \
+             'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=35, MinWords=15') AS snippet \
+         FROM statutes, websearch_to_tsquery('english', ",
+    );
+    query.push_bind(q);
+    query.push(") AS query WHERE search_vector @@ query");
+
+    if let Some(category) = &params.category {
+        query.push(" AND lower(category) = lower(");
+        query.push_bind(category);
+        query.push(")");
+    }
+
+    query.push(" ORDER BY rank DESC, year DESC LIMIT ");
+    query.push_bind(params.limit.unwrap_or(20).clamp(1, 100));
+
+    match query
+        .build_query_as::<StatuteSearchResult>()
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(results) => Json(results).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+async fn get_statute(State(state): State<AppState>, Path(id): Path<Uuid>) -> impl IntoResponse {
+    match sqlx::query_as::<_, Statute>(
+        "SELECT id, title, year, content, category, created_at FROM statutes WHERE id = $1",
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(statute) => Json(statute).into_response(),
+        Err(sqlx::Error::RowNotFound) => {
+            (StatusCode::NOT_FOUND, "Statute not found").into_response()
+        }
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
+
+/// The statute's full structure: parts, chapters and sections with their
+/// subsections and paragraphs nested inside, as in force on `?as_of=`.
+async fn list_provisions(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<AsOfQuery>,
+) -> impl IntoResponse {
+    let rows = match sqlx::query_as::<_, StatuteProvision>(
+        &PROVISIONS_AS_OF.replace("{from}", "statute_provisions"),
+    )
+    .bind(id)
+    .bind(params.as_of)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
+    };
+
+    if rows.is_empty() {
+        match sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM statutes WHERE id = $1)")
+            .bind(id)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return (StatusCode::NOT_FOUND, "Statute not found").into_response(),
+            Err(e) => {
+                eprintln!("Database error: {:?}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                    .into_response();
+            }
+        }
+    }
+
+    Json(provision_tree(rows)).into_response()
+}
+
+/// A section, subsection or paragraph by its path, e.g. `36/6/c` for
+/// Section 36(6)(c), with everything under it, as in force on `?as_of=`.
+async fn get_section(
+    State(state): State<AppState>,
+    Path((id, path)): Path<(Uuid, String)>,
+    Query(params): Query<AsOfQuery>,
+) -> impl IntoResponse {
+    let path = path.trim_matches('/').to_lowercase();
+    let query = format!(
+        "WITH RECURSIVE subtree AS ( \
+             SELECT * FROM statute_provisions WHERE statute_id = $1 AND path = $3 \
+             UNION ALL \
+             SELECT c.* FROM statute_provisions c JOIN subtree s ON c.parent_id = s.id \
+         ) {}",
+        PROVISIONS_AS_OF.replace("{from}", "subtree")
+    );
+
+    let rows = match sqlx::query_as::<_, StatuteProvision>(&query)
+        .bind(id)
+        .bind(params.as_of)
+        .bind(&path)
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response();
+        }
+    };
+
+    // The first row is the requested provision; it is missing if the path is
+    // unknown or the provision was not in force on that date.
+    match provision_tree(rows).into_iter().next() {
+        Some(provision) if provision.path.as_deref() == Some(path.as_str()) => {
+            Json(provision).into_response()
+        }
+        _ => (StatusCode::NOT_FOUND, "Provision not found").into_response(),
+    }
+}
+
+/// Every text a provision has had, oldest first. Empty if it has never been
+/// amended.
+async fn get_section_history(
+    State(state): State<AppState>,
+    Path((id, path)): Path<(Uuid, String)>,
+) -> impl IntoResponse {
+    let path = path.trim_matches('/').to_lowercase();
+
+    match sqlx::query_as::<_, ProvisionVersion>(
+        "SELECT v.id, v.heading, v.text, v.effective_from, v.effective_to, v.amended_by \
+         FROM statute_provision_versions v \
+         JOIN statute_provisions p ON p.id = v.provision_id \
+         WHERE p.statute_id = $1 AND p.path = $2 \
+         ORDER BY v.effective_from ASC NULLS FIRST",
+    )
+    .bind(id)
+    .bind(&path)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(versions) => Json(versions).into_response(),
+        Err(e) => {
+            eprintln!("Database error: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+        }
+    }
+}
@@ -7,6 +7,7 @@ use axum::{
 use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
 use serde::{Deserialize, Serialize};
 use std::env;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[allow(dead_code)]
@@ -15,9 +16,37 @@ pub struct Claims {
     pub exp: usize,
     pub role: String,
     pub email: Option<String>,
+    #[serde(default)]
+    pub app_metadata: Option<AppMetadata>,
+}
+
+/// Server-controlled user metadata. Only the service role can write it, so it
+/// is where admin and professional roles are granted.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AppMetadata {
+    pub role: Option<String>,
+}
+
+impl Claims {
+    /// The Supabase user id (`auth.users.id`) carried in `sub`.
+    pub fn user_id(&self) -> Option<Uuid> {
+        Uuid::parse_str(&self.sub).ok()
+    }
+
+    /// The platform role: `app_metadata.role` when set, otherwise the
+    /// Postgres role Supabase put in `role` (usually "authenticated").
+    pub fn app_role(&self) -> &str {
+        self.app_metadata
+            .as_ref()
+            .and_then(|m| m.role.as_deref())
+            .unwrap_or(&self.role)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.app_role() == "admin"
+    }
 }
 
-#[allow(dead_code)]
 pub async fn require_auth(mut req: Request, next: Next) -> Result<Response, StatusCode> {
     let auth_header = req
         .headers()
@@ -54,3 +83,12 @@ pub async fn require_auth(mut req: Request, next: Next) -> Result<Response, Stat
 
     Ok(next.run(req).await)
 }
+
+/// Lets only admins through. Must run after `require_auth`.
+pub async fn require_admin(req: Request, next: Next) -> Result<Response, StatusCode> {
+    match req.extensions().get::<Claims>() {
+        Some(claims) if claims.is_admin() => Ok(next.run(req).await),
+        Some(_) => Err(StatusCode::FORBIDDEN),
+        None => Err(StatusCode::UNAUTHORIZED),
+    }
+}
@@ -1,14 +1,19 @@
 mod api;
 mod auth;
 mod models;
+mod rate_limit;
 mod services;
 mod workers;
 use axum::http::{Method, header};
-use axum::{Router, routing::get};
+use axum::{
+    Router, middleware,
+    routing::{get, post},
+};
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use std::env;
 use std::net::SocketAddr;
 use std::str::FromStr;
+use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 
 #[tokio::main]
@@ -30,10 +35,34 @@ async fn main() {
         .expect("could not connect to database");
     println!("Database connected successfully.");
 
-    let state = models::db::AppState { db: pool };
+    let state = models::db::AppState {
+        db: pool,
+        rate_limiter: rate_limit::RateLimiter::default(),
+        ai: Arc::new(services::ai::AiService::from_env()),
+    };
 
-    // 👇 ADD THIS — starts the summarization worker in the background
-    tokio::spawn(workers::ai_summarizer::start_summarization_worker(state.clone()));
+    // 👇 ADD THIS — starts the summarization workers in the background
+    let summarization_workers = env::var("SUMMARIZATION_WORKERS")
+        .ok()
+        .and_then(|n| n.parse::<usize>().ok())
+        .unwrap_or(1);
+    for worker in 1..=summarization_workers {
+        tokio::spawn(workers::ai_summarizer::start_summarization_worker(
+            state.clone(),
+            worker,
+        ));
+    }
+    tokio::spawn(workers::statute_indexer::index_statutes(state.clone()));
+    tokio::spawn(workers::citation_extractor::start_citation_worker(
+        state.clone(),
+    ));
+    tokio::spawn(workers::treatment_classifier::start_treatment_worker(
+        state.clone(),
+    ));
+    tokio::spawn(workers::case_embedder::start_embedding_worker(
+        state.clone(),
+        state.ai.clone(),
+    ));
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -47,11 +76,38 @@ async fn main() {
         ])
         .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, header::ACCEPT]);
 
+    let rate_limited = || middleware::from_fn_with_state(state.clone(), rate_limit::enforce);
+
+    // Routes that need a signed-in user; handlers read `Extension<Claims>`.
+    // The rate limit layer sits inside auth so it can key on the user.
+    let protected_routes = Router::new()
+        .nest("/auth", api::auth::router())
+        .nest(
+            "/chat",
+            api::chat::router().merge(api::chat::lexa_router().route_layer(rate_limited())),
+        )
+        .nest("/conversations", api::conversations::router())
+        .route("/exams/{id}/answer", post(api::exams::submit_answer))
+        .route(
+            "/exams/{id}/essay",
+            post(api::exams::submit_essay).route_layer(rate_limited()),
+        )
+        .route("/exams/{id}/essay", get(api::exams::list_essay_attempts))
+        .nest("/practice", api::practice::router())
+        .nest("/review", api::review::router())
+        .nest(
+            "/admin",
+            api::admin::router().route_layer(middleware::from_fn(auth::require_admin)),
+        )
+        .route_layer(middleware::from_fn(auth::require_auth));
+
     let api_routes = Router::new()
         .nest("/cases", api::cases::router())
+        .route("/cases/semantic", get(api::cases::semantic_search).route_layer(rate_limited()))
         .nest("/courses", api::courses::router())
-        .nest("/auth", api::auth::router())
-        .nest("/chat", api::chat::router())
+        .nest("/exams", api::exams::router())
+        .nest("/statutes", api::statutes::router())
+        .merge(protected_routes)
         .with_state(state.clone());
 
     let app = Router::new()
@@ -68,7 +124,12 @@ async fn main() {
     println!("listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 async fn root() -> &'static str {
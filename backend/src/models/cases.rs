@@ -13,7 +13,10 @@ pub struct Case {
     pub topic: Option<String>,
     pub difficulty: Option<String>,
     pub summary: Option<String>,
-    pub content: String,
+    pub content: Option<String>,
+    /// How later cases have treated this one: `good_law`, `questioned`,
+    /// `overruled` or `unknown`. Kept current by a trigger on `case_citations`.
+    pub good_law_status: String,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -24,3 +27,160 @@ pub struct CaseSearchQuery {
     pub topic: Option<String>,
     pub limit: Option<i64>,
 }
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CaseSearchResult {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub case: Case,
+    pub rank: f32,
+    pub snippet: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SemanticSearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SemanticCaseResult {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub case: Case,
+    /// Cosine distance to the query embedding (0 = identical, 2 = opposite).
+    pub distance: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JudgePosition {
+    /// Delivered the lead judgment.
+    Lead,
+    Concurring,
+    Dissenting,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JudgeOpinion {
+    pub name: String,
+    pub position: JudgePosition,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A case brief in the IRAC layout students use, one row of `case_briefs`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CaseBrief {
+    pub facts: String,
+    pub issues: Vec<String>,
+    pub holding: String,
+    pub ratio_decidendi: String,
+    #[serde(default)]
+    pub obiter_dicta: Vec<String>,
+    pub disposition: String,
+    #[sqlx(json)]
+    #[serde(default)]
+    pub judges: Vec<JudgeOpinion>,
+}
+
+/// `GET /api/cases/{id}`: the case plus its brief, once the worker has made one.
+#[derive(Debug, Serialize)]
+pub struct CaseDetail {
+    #[serde(flatten)]
+    pub case: Case,
+    pub brief: Option<CaseBrief>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CitationLookupQuery {
+    pub c: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCaseRequest {
+    pub title: String,
+    pub citation: String,
+    pub court: String,
+    pub year: i32,
+    pub topic: Option<String>,
+    pub difficulty: Option<String>,
+    pub content: Option<String>,
+}
+
+/// An authority a judgment cites (`GET /api/cases/{id}/citations`): a case
+/// or a statute in the library, or a reported citation to a case the
+/// library does not hold yet, in which case both ids are null.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CitedAuthority {
+    pub id: Uuid,
+    pub cited_text: String,
+    pub context: Option<String>,
+    pub cited_case_id: Option<Uuid>,
+    pub cited_case_title: Option<String>,
+    pub cited_case_citation: Option<String>,
+    pub cited_case_year: Option<i32>,
+    pub cited_statute_id: Option<Uuid>,
+    pub cited_statute_title: Option<String>,
+    pub treatment: Option<String>,
+    pub treatment_note: Option<String>,
+}
+
+/// A later judgment citing a case (`GET /api/cases/{id}/cited-by`).
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CitingCase {
+    pub citation_id: Uuid,
+    pub case_id: Uuid,
+    pub title: String,
+    pub citation: String,
+    pub court: String,
+    pub year: i32,
+    /// The paragraph where the case is cited.
+    pub context: Option<String>,
+    /// How the citing case treated this one, once classified.
+    pub treatment: Option<String>,
+    pub treatment_note: Option<String>,
+}
+
+/// How a later judgment treats a precedent it cites. `Cited` is a passing
+/// reference that neither relies on nor departs from it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Treatment {
+    Cited,
+    Followed,
+    Applied,
+    Distinguished,
+    Doubted,
+    Overruled,
+}
+
+impl Treatment {
+    pub const ALL: [Treatment; 6] = [
+        Treatment::Cited,
+        Treatment::Followed,
+        Treatment::Applied,
+        Treatment::Distinguished,
+        Treatment::Doubted,
+        Treatment::Overruled,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Treatment::Cited => "cited",
+            Treatment::Followed => "followed",
+            Treatment::Applied => "applied",
+            Treatment::Distinguished => "distinguished",
+            Treatment::Doubted => "doubted",
+            Treatment::Overruled => "overruled",
+        }
+    }
+}
+
+/// `PUT /api/admin/citations/{id}/treatment`: a manual classification,
+/// which the classifier will not overwrite.
+#[derive(Debug, Deserialize)]
+pub struct SetTreatmentRequest {
+    pub treatment: Treatment,
+    pub note: Option<String>,
+}
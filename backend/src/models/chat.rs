@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Conversation {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub title: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ChatMessage {
+    pub id: Uuid,
+    pub conversation_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub role: String, // 'user' or 'assistant'
+    pub content: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateConversationRequest {
+    pub title: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RenameConversationRequest {
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatHistoryQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// One page of a user's chat messages, newest first.
+#[derive(Debug, Serialize)]
+pub struct ChatHistoryPage {
+    pub messages: Vec<ChatMessage>,
+    pub page: i64,
+    pub per_page: i64,
+    pub total: i64,
+}
@@ -1,7 +1,12 @@
+use crate::rate_limit::RateLimiter;
+use crate::services::ai::AiService;
 use sqlx::PgPool;
+use std::sync::Arc;
 
 #[derive(Clone)]
 #[allow(dead_code)]
 pub struct AppState {
     pub db: PgPool,
+    pub rate_limiter: RateLimiter,
+    pub ai: Arc<AiService>,
 }
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// A row of `exam_questions` as students see it before answering: the
+/// correct answer and explanation are left out.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ExamQuestion {
+    pub id: Uuid,
+    pub question: String,
+    /// `multiple_choice` or `essay`.
+    pub question_type: String,
+    /// The choices as a JSON array of strings; null for essays.
+    pub options: Option<Value>,
+    pub law_category: String,
+    pub difficulty: Option<String>,
+    /// e.g. "Bar Finals" or "LLB".
+    pub source: Option<String>,
+    pub year: Option<i32>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// The hidden half of a question, read only to mark an answer.
+#[derive(Debug, sqlx::FromRow)]
+pub struct AnswerKey {
+    pub question_type: String,
+    pub options: Option<Value>,
+    pub correct_answer: String,
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExamQuestionQuery {
+    /// `multiple_choice` or `essay`.
+    #[serde(rename = "type")]
+    pub question_type: Option<String>,
+    pub category: Option<String>,
+    pub difficulty: Option<String>,
+    pub year: Option<i32>,
+    pub source: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitAnswerRequest {
+    /// The option text, or its letter ("C") for multiple choice.
+    pub answer: String,
+}
+
+/// `POST /api/exams/{id}/answer`: the only place the answer is revealed.
+#[derive(Debug, Serialize)]
+pub struct AnswerResult {
+    pub question_id: Uuid,
+    pub correct: bool,
+    pub correct_answer: String,
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionType {
+    MultipleChoice,
+    /// A problem question answered in prose; `correct_answer` holds the
+    /// model answer.
+    Essay,
+}
+
+impl QuestionType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            QuestionType::MultipleChoice => "multiple_choice",
+            QuestionType::Essay => "essay",
+        }
+    }
+}
+
+/// A row of `generated_questions`: an AI-drafted question waiting for a
+/// moderator, or the record of their decision.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct QuestionDraft {
+    pub id: Uuid,
+    /// `pending`, `approved` or `rejected`.
+    pub status: String,
+    pub question_type: String,
+    pub question: String,
+    pub options: Option<Value>,
+    pub correct_answer: String,
+    pub explanation: Option<String>,
+    pub law_category: String,
+    pub difficulty: Option<String>,
+    pub source_case_id: Option<Uuid>,
+    pub source_topic_id: Option<Uuid>,
+    pub reviewed_by: Option<Uuid>,
+    pub review_note: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// The `exam_questions` row created on approval.
+    pub published_question_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `POST /api/admin/questions/generate`: draft questions from one case or
+/// one course topic.
+#[derive(Debug, Deserialize)]
+pub struct GenerateQuestionsRequest {
+    pub case_id: Option<Uuid>,
+    pub topic_id: Option<Uuid>,
+    pub question_type: QuestionType,
+    pub count: Option<usize>,
+    /// Defaults to the case's category or the topic's course.
+    pub law_category: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DraftListQuery {
+    /// Defaults to `pending`.
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Approves a draft, with any corrections the moderator made. Fields left
+/// out keep the drafted value.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ApproveDraftRequest {
+    pub question: Option<String>,
+    pub options: Option<Vec<String>>,
+    pub correct_answer: Option<String>,
+    pub explanation: Option<String>,
+    pub law_category: Option<String>,
+    pub difficulty: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct RejectDraftRequest {
+    pub note: Option<String>,
+}
+
+/// How an essay question is marked, stored in `exam_questions.rubric`.
+/// Marks are whole numbers; the question is out of their sum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rubric {
+    /// The issues a good answer spots.
+    pub issues: Vec<RubricIssue>,
+    /// Authorities a good answer cites, checked against the text itself
+    /// rather than by the AI.
+    #[serde(default)]
+    pub authorities: Vec<RubricAuthority>,
+    pub conclusion: RubricConclusion,
+}
+
+impl Rubric {
+    pub fn max_score(&self) -> u32 {
+        self.issues.iter().map(|i| i.marks).sum::<u32>()
+            + self.authorities.iter().map(|a| a.marks).sum::<u32>()
+            + self.conclusion.marks
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricIssue {
+    pub issue: String,
+    pub marks: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricAuthority {
+    /// A case name ("Donoghue v Stevenson") or statute title.
+    pub name: String,
+    /// The reported citation, for a case.
+    #[serde(default)]
+    pub citation: Option<String>,
+    pub marks: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricConclusion {
+    pub conclusion: String,
+    pub marks: u32,
+}
+
+/// How fully an answer deals with an issue or reaches the conclusion. A
+/// partial answer earns half the marks.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Coverage {
+    Full,
+    Partial,
+    Missed,
+}
+
+impl Coverage {
+    pub fn award(self, marks: u32) -> f64 {
+        match self {
+            Coverage::Full => marks as f64,
+            Coverage::Partial => marks as f64 / 2.0,
+            Coverage::Missed => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitEssayRequest {
+    pub answer: String,
+}
+
+/// A case in the library, as linked from essay feedback.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LibraryCase {
+    pub id: Uuid,
+    pub title: String,
+    pub citation: String,
+    pub good_law_status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueMark {
+    pub issue: String,
+    pub marks: u32,
+    pub awarded: f64,
+    pub coverage: Coverage,
+    pub comment: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorityMark {
+    pub name: String,
+    pub citation: Option<String>,
+    pub marks: u32,
+    pub awarded: f64,
+    pub cited: bool,
+    /// The authority in the library, when it is a case we hold.
+    pub case: Option<LibraryCase>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConclusionMark {
+    pub conclusion: String,
+    pub marks: u32,
+    pub awarded: f64,
+    pub coverage: Coverage,
+    pub comment: String,
+}
+
+/// How an essay answer was marked, stored in `essay_attempts.marking`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EssayMarking {
+    pub issues: Vec<IssueMark>,
+    pub authorities: Vec<AuthorityMark>,
+    pub conclusion: ConclusionMark,
+    /// The rubric issues the answer did not deal with at all.
+    pub missed_issues: Vec<String>,
+    /// The rubric authorities the answer should have cited.
+    pub missed_authorities: Vec<AuthorityMark>,
+    /// Other cases the AI says the answer should have relied on. Only
+    /// suggestions found in the library are kept.
+    pub further_authorities: Vec<LibraryCase>,
+    /// Library cases the answer cites, with their good-law status so that
+    /// reliance on an overruled case can be flagged.
+    pub cited_cases: Vec<LibraryCase>,
+    pub feedback: String,
+}
+
+/// A row of `essay_attempts`.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EssayAttempt {
+    pub id: Uuid,
+    pub question_id: Uuid,
+    pub answer: String,
+    pub score: f64,
+    pub max_score: i32,
+    #[sqlx(json)]
+    pub marking: EssayMarking,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `POST /api/exams/{id}/essay`: the marked attempt, with the model answer
+/// revealed.
+#[derive(Debug, Serialize)]
+pub struct EssayResult {
+    #[serde(flatten)]
+    pub attempt: EssayAttempt,
+    pub model_answer: String,
+    pub explanation: Option<String>,
+}
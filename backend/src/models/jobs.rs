@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One row of `summarization_jobs`. `status` moves pending → running →
+/// succeeded, or back to pending with a later `next_run_at` after a failure,
+/// until `max_attempts` is reached and the job is parked as failed.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SummarizationJob {
+    pub id: Uuid,
+    pub case_id: Uuid,
+    pub status: String,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub next_run_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobListQuery {
+    /// Defaults to `failed`.
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
@@ -1,4 +1,10 @@
 pub mod cases;
+pub mod chat;
 pub mod courses;
 pub mod db;
+pub mod exams;
+pub mod jobs;
+pub mod practice;
+pub mod review;
+pub mod statutes;
 pub mod user;
@@ -0,0 +1,77 @@
+use crate::models::exams::ExamQuestion;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PracticeSession {
+    pub id: Uuid,
+    /// `in_progress` or `completed`.
+    pub status: String,
+    pub time_limit_secs: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Set once the session is graded.
+    pub score: Option<i32>,
+    pub total: i32,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// `POST /api/practice`. Either name how many questions to draw from each
+/// category, or give a `count` to draw at random (optionally narrowed by
+/// `category`).
+#[derive(Debug, Deserialize)]
+pub struct StartSessionRequest {
+    /// e.g. `{ "Constitutional Law": 5, "Law of Tort": 5 }`.
+    #[serde(default)]
+    pub per_category: BTreeMap<String, i64>,
+    pub count: Option<i64>,
+    pub category: Option<String>,
+    pub difficulty: Option<String>,
+    pub source: Option<String>,
+    pub time_limit_minutes: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveAnswerRequest {
+    pub question_id: Uuid,
+    pub answer: String,
+}
+
+/// A question in a session with the user's answer. The correct answer and
+/// explanation stay empty until the session is graded.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct SessionQuestion {
+    pub position: i32,
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub question: ExamQuestion,
+    pub answer: Option<String>,
+    pub correct: Option<bool>,
+    pub correct_answer: Option<String>,
+    pub explanation: Option<String>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CategoryScore {
+    pub law_category: String,
+    pub total: i64,
+    pub answered: i64,
+    pub correct: i64,
+}
+
+/// `GET /api/practice/{id}`: the session, its questions and, once graded,
+/// the score per category.
+#[derive(Debug, Serialize)]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    pub session: PracticeSession,
+    pub questions: Vec<SessionQuestion>,
+    pub categories: Vec<CategoryScore>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionListQuery {
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,63 @@
+use crate::models::exams::{AnswerResult, ExamQuestion};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A row of `review_items`: one missed exam question or flagged case and
+/// its SM-2 schedule.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReviewItem {
+    pub id: Uuid,
+    pub question_id: Option<Uuid>,
+    pub case_id: Option<Uuid>,
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+    pub lapses: i32,
+    pub due_on: NaiveDate,
+    pub last_reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What a flagged case shows in the review queue.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ReviewCase {
+    pub id: Uuid,
+    pub title: String,
+    pub citation: String,
+    pub court: String,
+    pub year: i32,
+    pub summary: Option<String>,
+    pub good_law_status: String,
+}
+
+/// An item in `GET /api/review/due`, with the question (answer hidden) or
+/// case to review.
+#[derive(Debug, Serialize)]
+pub struct DueItem {
+    #[serde(flatten)]
+    pub item: ReviewItem,
+    pub question: Option<ExamQuestion>,
+    pub case: Option<ReviewCase>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DueQuery {
+    pub limit: Option<i64>,
+}
+
+/// `POST /api/review/{id}`. `quality` is the SM-2 grade, 0 (blackout) to 5
+/// (perfect recall). For a question, `answer` may be sent instead and is
+/// marked: correct counts as 4, wrong as 1.
+#[derive(Debug, Deserialize)]
+pub struct ReviewRequest {
+    pub quality: Option<u8>,
+    pub answer: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReviewOutcome {
+    pub item: ReviewItem,
+    /// The marked answer, when one was sent.
+    pub answer: Option<AnswerResult>,
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Statute {
+    pub id: Uuid,
+    pub title: String,
+    pub year: Option<i32>,
+    pub content: String,
+    /// 'Constitution', 'Act' or 'Decree'.
+    pub category: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A statute without its text, for listings; a whole Act can run to
+/// megabytes.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct StatuteSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub year: Option<i32>,
+    pub category: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatuteListQuery {
+    pub category: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatuteSearchQuery {
+    pub q: String,
+    pub category: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct StatuteSearchResult {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub statute: StatuteSummary,
+    pub rank: f32,
+    pub snippet: Option<String>,
+}
+
+/// A row of `statute_provisions` with the heading and text of the version
+/// in force on the requested date.
+#[derive(Debug, sqlx::FromRow)]
+pub struct StatuteProvision {
+    pub id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub kind: String,
+    pub number: String,
+    pub heading: Option<String>,
+    pub text: String,
+    pub path: Option<String>,
+    pub effective_from: Option<NaiveDate>,
+    pub effective_to: Option<NaiveDate>,
+    pub amended_by: Option<String>,
+}
+
+/// A provision with everything under it, as returned by the API.
+#[derive(Debug, Serialize)]
+pub struct ProvisionNode {
+    pub id: Uuid,
+    pub kind: String,
+    pub number: String,
+    pub heading: Option<String>,
+    pub text: String,
+    /// Stable address under `/api/statutes/{id}/sections/`, e.g. "36/6/c".
+    pub path: Option<String>,
+    /// How the provision is cited, e.g. "Section 36(6)(c)".
+    pub citation: Option<String>,
+    /// When this text came into force; null if it is the enacted text.
+    pub effective_from: Option<NaiveDate>,
+    /// When this text stopped being in force; null if it still is.
+    pub effective_to: Option<NaiveDate>,
+    /// The amending instrument that introduced this text.
+    pub amended_by: Option<String>,
+    pub children: Vec<ProvisionNode>,
+}
+
+impl ProvisionNode {
+    pub fn new(row: StatuteProvision, children: Vec<ProvisionNode>) -> Self {
+        let citation = row.path.as_deref().map(|path| {
+            let mut parts = path.split('/');
+            let section = parts.next().unwrap_or_default().to_uppercase();
+            parts.fold(format!("Section {}", section), |cite, part| {
+                format!("{}({})", cite, part)
+            })
+        });
+
+        Self {
+            id: row.id,
+            kind: row.kind,
+            number: row.number,
+            heading: row.heading,
+            text: row.text,
+            path: row.path,
+            citation,
+            effective_from: row.effective_from,
+            effective_to: row.effective_to,
+            amended_by: row.amended_by,
+            children,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AsOfQuery {
+    /// `YYYY-MM-DD`; defaults to today.
+    pub as_of: Option<NaiveDate>,
+}
+
+/// One entry in a provision's amendment history.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct ProvisionVersion {
+    pub id: Uuid,
+    pub heading: Option<String>,
+    pub text: String,
+    pub effective_from: Option<NaiveDate>,
+    pub effective_to: Option<NaiveDate>,
+    pub amended_by: Option<String>,
+}
+
+/// Records an amendment to a provision: new text from `effective_from`, or
+/// its repeal from that date when `repeal` is set.
+#[derive(Debug, Deserialize)]
+pub struct AmendProvisionRequest {
+    pub text: Option<String>,
+    pub heading: Option<String>,
+    pub effective_from: NaiveDate,
+    pub amended_by: String,
+    #[serde(default)]
+    pub repeal: bool,
+}
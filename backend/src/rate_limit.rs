@@ -0,0 +1,241 @@
+use crate::auth::Claims;
+use crate::models::db::AppState;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const WINDOW: Duration = Duration::from_secs(60);
+// Stale windows are swept once the map grows past this many keys.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// Limits for one class of caller, picked from `Claims::app_role`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tier {
+    Guest,
+    Student,
+    Professional,
+    Admin,
+}
+
+impl Tier {
+    pub fn from_role(role: &str) -> Self {
+        match role {
+            "admin" => Tier::Admin,
+            "professional" => Tier::Professional,
+            // Supabase issues role "authenticated" unless a custom claim is set.
+            _ => Tier::Student,
+        }
+    }
+
+    fn requests_per_minute(self) -> u32 {
+        match self {
+            Tier::Guest => 10,
+            Tier::Student => 20,
+            Tier::Professional => 60,
+            Tier::Admin => 600,
+        }
+    }
+
+    fn daily_requests(self) -> i32 {
+        match self {
+            Tier::Guest => 20,
+            Tier::Student => 200,
+            Tier::Professional => 1_000,
+            Tier::Admin => i32::MAX,
+        }
+    }
+
+    fn daily_tokens(self) -> i64 {
+        match self {
+            Tier::Guest => 20_000,
+            Tier::Student => 200_000,
+            Tier::Professional => 1_000_000,
+            Tier::Admin => i64::MAX,
+        }
+    }
+}
+
+/// Who a request is metered against: the signed-in user, or the client IP for
+/// guests. Inserted as a request extension so handlers can record token usage.
+#[derive(Debug, Clone)]
+pub struct UsageSubject(pub String);
+
+/// Fixed one-minute request windows, kept in memory per subject.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    windows: Arc<Mutex<HashMap<String, (Instant, u32)>>>,
+}
+
+struct WindowState {
+    limit: u32,
+    remaining: u32,
+    reset: Duration,
+    allowed: bool,
+}
+
+impl RateLimiter {
+    fn hit(&self, key: &str, limit: u32) -> WindowState {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if windows.len() > MAX_TRACKED_KEYS {
+            windows.retain(|_, (started, _)| now.duration_since(*started) < WINDOW);
+        }
+
+        let (started, count) = windows.entry(key.to_string()).or_insert((now, 0));
+        if now.duration_since(*started) >= WINDOW {
+            *started = now;
+            *count = 0;
+        }
+
+        let allowed = *count < limit;
+        if allowed {
+            *count += 1;
+        }
+
+        WindowState {
+            limit,
+            remaining: limit.saturating_sub(*count),
+            reset: WINDOW.saturating_sub(now.duration_since(*started)),
+            allowed,
+        }
+    }
+}
+
+/// Throttles routes that call the AI. Every request counts towards the
+/// caller's per-minute limit and towards the daily request and token quota
+/// kept in `ai_usage_daily`, so only layer it over routes that call a model.
+/// Responses carry `RateLimit-Limit`, `RateLimit-Remaining` and
+/// `RateLimit-Reset`, and a 429 adds `Retry-After`.
+pub async fn enforce(State(state): State<AppState>, mut req: Request, next: Next) -> Response {
+    let (subject, tier) = match req.extensions().get::<Claims>() {
+        Some(claims) => (
+            format!("user:{}", claims.sub),
+            Tier::from_role(claims.app_role()),
+        ),
+        None => (format!("ip:{}", client_ip(&req)), Tier::Guest),
+    };
+
+    let window = state.rate_limiter.hit(&subject, tier.requests_per_minute());
+    if !window.allowed {
+        let mut response = (
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many requests, please slow down",
+        )
+            .into_response();
+        set_headers(response.headers_mut(), &window);
+        set_retry_after(response.headers_mut(), window.reset.as_secs().max(1));
+        return response;
+    }
+
+    match consume_daily_request(&state.db, &subject, tier).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Daily LEXA quota reached, it resets at midnight UTC",
+            )
+                .into_response();
+            set_headers(response.headers_mut(), &window);
+            set_retry_after(response.headers_mut(), seconds_until_midnight_utc());
+            return response;
+        }
+        Err(e) => {
+            // Fail open: a quota bookkeeping error should not take LEXA down.
+            eprintln!("Failed to check daily quota for {}: {:?}", subject, e);
+        }
+    }
+
+    req.extensions_mut().insert(UsageSubject(subject));
+
+    let mut response = next.run(req).await;
+    set_headers(response.headers_mut(), &window);
+    response
+}
+
+/// Adds tokens spent by a generation to today's usage for `subject`.
+pub async fn record_tokens(db: &PgPool, subject: &UsageSubject, tokens: i64) {
+    if let Err(e) = sqlx::query(
+        "INSERT INTO ai_usage_daily (subject, usage_date, requests, tokens) \
+         VALUES ($1, CURRENT_DATE, 0, $2) \
+         ON CONFLICT (subject, usage_date) DO UPDATE SET tokens = ai_usage_daily.tokens + EXCLUDED.tokens",
+    )
+    .bind(&subject.0)
+    .bind(tokens)
+    .execute(db)
+    .await
+    {
+        eprintln!("Failed to record token usage for {}: {:?}", subject.0, e);
+    }
+}
+
+// Counts the request only if the subject is still under both daily limits;
+// returns false when the quota is exhausted.
+async fn consume_daily_request(
+    db: &PgPool,
+    subject: &str,
+    tier: Tier,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query_scalar::<_, i32>(
+        "INSERT INTO ai_usage_daily (subject, usage_date, requests, tokens) \
+         VALUES ($1, CURRENT_DATE, 1, 0) \
+         ON CONFLICT (subject, usage_date) DO UPDATE SET requests = ai_usage_daily.requests + 1 \
+         WHERE ai_usage_daily.requests < $2 AND ai_usage_daily.tokens < $3 \
+         RETURNING requests",
+    )
+    .bind(subject)
+    .bind(tier.daily_requests())
+    .bind(tier.daily_tokens())
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+fn client_ip(req: &Request) -> String {
+    // Clients can send their own X-Forwarded-For, so only the last entry,
+    // appended by our proxy (Railway), can be trusted.
+    req.headers()
+        .get("x-forwarded-for")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.rsplit(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+        .or_else(|| {
+            req.extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|info| info.0.ip().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn set_headers(headers: &mut HeaderMap, window: &WindowState) {
+    headers.insert("ratelimit-limit", HeaderValue::from(window.limit));
+    headers.insert("ratelimit-remaining", HeaderValue::from(window.remaining));
+    headers.insert(
+        "ratelimit-reset",
+        HeaderValue::from(window.reset.as_secs().max(1)),
+    );
+}
+
+fn set_retry_after(headers: &mut HeaderMap, seconds: u64) {
+    headers.insert("retry-after", HeaderValue::from(seconds));
+}
+
+fn seconds_until_midnight_utc() -> u64 {
+    let now = Utc::now();
+    let midnight = (now.date_naive() + ChronoDuration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .map(|t| t.and_utc())
+        .unwrap_or(now);
+    (midnight - now).num_seconds().max(1) as u64
+}
@@ -0,0 +1,226 @@
+use crate::services::citations::{citation_key, find_citations};
+use crate::services::text::paragraph_at;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// Enough of the citing paragraph to judge how the authority was used.
+const CONTEXT_CHARS: usize = 1200;
+// Shorter case names ("A v B") match too much unrelated text.
+const MIN_CASE_NAME_CHARS: usize = 10;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct KnownCase {
+    pub id: Uuid,
+    pub title: String,
+    pub citation_key: String,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct KnownStatute {
+    pub id: Uuid,
+    pub title: String,
+}
+
+/// One outgoing link of a judgment, before it is stored in `case_citations`.
+#[derive(Debug, Clone)]
+pub struct ExtractedCitation {
+    pub cited_case_id: Option<Uuid>,
+    pub cited_statute_id: Option<Uuid>,
+    pub cited_text: String,
+    pub cited_key: Option<String>,
+    pub context: String,
+    /// Byte offset of the first mention.
+    pub offset: usize,
+}
+
+/// Finds the authorities a judgment relies on: reported citations such as
+/// "(1996) 9 NWLR (Pt. 475) 710", case names from the library ("Donoghue v
+/// Stevenson"), and statute titles. Each authority is linked once, at its
+/// first mention. Reported citations to cases the library lacks are kept
+/// unresolved with their key.
+pub fn extract_citations(
+    case_id: Uuid,
+    content: &str,
+    cases: &[KnownCase],
+    statutes: &[KnownStatute],
+) -> Vec<ExtractedCitation> {
+    let by_key: HashMap<&str, Uuid> = cases
+        .iter()
+        .map(|c| (c.citation_key.as_str(), c.id))
+        .collect();
+    let mut seen_keys = HashSet::new();
+    let mut seen_cases = HashSet::from([case_id]);
+    let mut found = Vec::new();
+
+    for m in find_citations(content) {
+        let key = citation_key(&m.citation.to_string());
+        if !seen_keys.insert(key.clone()) {
+            continue;
+        }
+        let cited_case_id = by_key.get(key.as_str()).copied();
+        if let Some(id) = cited_case_id
+            && !seen_cases.insert(id)
+        {
+            continue;
+        }
+
+        found.push(ExtractedCitation {
+            cited_case_id,
+            cited_statute_id: None,
+            cited_text: content[m.start..m.end].to_string(),
+            cited_key: Some(key),
+            context: paragraph_at(content, m.start, CONTEXT_CHARS),
+            offset: m.start,
+        });
+    }
+
+    // Lower-casing ASCII only keeps byte offsets aligned with `content`.
+    let haystack = content.to_ascii_lowercase();
+
+    for case in cases {
+        let name = case.title.trim();
+        if seen_cases.contains(&case.id)
+            || name.chars().count() < MIN_CASE_NAME_CHARS
+            || !name.to_ascii_lowercase().contains(" v ")
+        {
+            continue;
+        }
+        if let Some(start) = find_name(&haystack, name) {
+            seen_cases.insert(case.id);
+            found.push(ExtractedCitation {
+                cited_case_id: Some(case.id),
+                cited_statute_id: None,
+                cited_text: content[start..start + name.len()].to_string(),
+                cited_key: Some(case.citation_key.clone()),
+                context: paragraph_at(content, start, CONTEXT_CHARS),
+                offset: start,
+            });
+        }
+    }
+
+    for statute in statutes {
+        let name = statute.title.trim();
+        if let Some(start) = find_name(&haystack, name) {
+            found.push(ExtractedCitation {
+                cited_case_id: None,
+                cited_statute_id: Some(statute.id),
+                cited_text: content[start..start + name.len()].to_string(),
+                cited_key: None,
+                context: paragraph_at(content, start, CONTEXT_CHARS),
+                offset: start,
+            });
+        }
+    }
+
+    found.sort_by_key(|c| c.offset);
+    found
+}
+
+/// First whole-word, case-insensitive occurrence of `name` in the
+/// lower-cased text.
+pub fn find_name(haystack: &str, name: &str) -> Option<usize> {
+    let needle = name.to_ascii_lowercase();
+    if needle.is_empty() {
+        return None;
+    }
+
+    haystack.match_indices(&needle).map(|(i, _)| i).find(|&i| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+// A classification saved on a link before re-extraction.
+#[derive(Debug, sqlx::FromRow)]
+struct PriorTreatment {
+    cited_case_id: Option<Uuid>,
+    context: Option<String>,
+    treatment: Option<String>,
+    treatment_note: Option<String>,
+    classified_by: Option<String>,
+    classified_at: Option<DateTime<Utc>>,
+}
+
+/// Replaces a judgment's outgoing links and records the text hash they were
+/// extracted from, in one transaction. A link to the same case in an
+/// unchanged paragraph keeps its treatment, and an admin's treatment is kept
+/// even when the paragraph changed.
+pub async fn replace_citations(
+    db: &PgPool,
+    case_id: Uuid,
+    content_hash: &str,
+    citations: &[ExtractedCitation],
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let prior = sqlx::query_as::<_, PriorTreatment>(
+        "DELETE FROM case_citations WHERE citing_case_id = $1 \
+         RETURNING cited_case_id, context, treatment, treatment_note, classified_by, classified_at",
+    )
+    .bind(case_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let prior: Vec<PriorTreatment> = prior
+        .into_iter()
+        .filter(|p| p.treatment.is_some())
+        .collect();
+
+    for (position, citation) in citations.iter().enumerate() {
+        let kept = citation.cited_case_id.and_then(|cited| {
+            let same_case = |p: &&PriorTreatment| p.cited_case_id == Some(cited);
+            prior
+                .iter()
+                .filter(same_case)
+                .find(|p| p.context.as_deref() == Some(citation.context.as_str()))
+                .or_else(|| {
+                    prior
+                        .iter()
+                        .filter(same_case)
+                        .find(|p| p.classified_by.as_deref() == Some("admin"))
+                })
+        });
+
+        sqlx::query(
+            "INSERT INTO case_citations (citing_case_id, cited_case_id, cited_statute_id, cited_text, cited_key, context, position, \
+                 treatment, treatment_note, classified_by, classified_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)",
+        )
+        .bind(case_id)
+        .bind(citation.cited_case_id)
+        .bind(citation.cited_statute_id)
+        .bind(&citation.cited_text)
+        .bind(&citation.cited_key)
+        .bind(&citation.context)
+        .bind(position as i32)
+        .bind(kept.and_then(|p| p.treatment.as_deref()))
+        .bind(kept.and_then(|p| p.treatment_note.as_deref()))
+        .bind(kept.and_then(|p| p.classified_by.as_deref()))
+        .bind(kept.and_then(|p| p.classified_at))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE cases SET citations_hash = $1 WHERE id = $2")
+        .bind(content_hash)
+        .bind(case_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await
+}
+
+/// Points unresolved citations at cases added since they were extracted.
+pub async fn resolve_pending(db: &PgPool) -> Result<u64, sqlx::Error> {
+    sqlx::query(
+        "UPDATE case_citations cc SET cited_case_id = c.id \
+         FROM cases c \
+         WHERE cc.cited_case_id IS NULL AND cc.cited_statute_id IS NULL \
+           AND cc.cited_key = c.citation_key AND c.id <> cc.citing_case_id",
+    )
+    .execute(db)
+    .await
+    .map(|r| r.rows_affected())
+}
@@ -0,0 +1,546 @@
+use serde::Serialize;
+use std::fmt;
+
+// Report series we know, keyed by their letters in upper case with spaces and
+// dots removed, so "All N.L.R." and "ALLNLR" both find "All NLR".
+const KNOWN_SERIES: [(&str, &str); 26] = [
+    ("NWLR", "NWLR"),
+    ("SC", "SC"),
+    ("SCNJ", "SCNJ"),
+    ("SCNLR", "SCNLR"),
+    ("SCM", "SCM"),
+    ("LPELR", "LPELR"),
+    ("FWLR", "FWLR"),
+    ("ALLNLR", "All NLR"),
+    ("NLR", "NLR"),
+    ("NMLR", "NMLR"),
+    ("NSCC", "NSCC"),
+    ("NCLR", "NCLR"),
+    ("WRNLR", "WRNLR"),
+    ("ENLR", "ENLR"),
+    ("NNLR", "NNLR"),
+    ("WACA", "WACA"),
+    ("CHR", "CHR"),
+    ("AC", "AC"),
+    ("QB", "QB"),
+    ("KB", "KB"),
+    ("CH", "Ch"),
+    ("WLR", "WLR"),
+    ("ALLER", "All ER"),
+    ("EWHC", "EWHC"),
+    ("UKHL", "UKHL"),
+    ("UKSC", "UKSC"),
+];
+
+// Series whose volumes are split into numbered parts: "(Pt. 475)".
+const PART_SERIES: [&str; 2] = ["NWLR", "FWLR"];
+// Longest abbreviation accepted for a series not in `KNOWN_SERIES`.
+const MAX_UNKNOWN_SERIES_CHARS: usize = 6;
+
+/// A law report citation broken into its parts, e.g.
+/// `(1996) 9 NWLR (Pt. 475) 710` or `(2013) LPELR-20385(SC)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Citation {
+    pub year: i32,
+    /// `[1932] AC 562`-style citations, where the year identifies the volume.
+    pub square_brackets: bool,
+    /// A number or a range such as "8-11".
+    pub volume: Option<String>,
+    pub series: String,
+    /// A number ("475") or, in some series, a Roman numeral ("II").
+    pub part: Option<String>,
+    /// The first page, or the document number for LPELR.
+    pub page: String,
+    /// The court in an LPELR citation, e.g. "SC" or "CA".
+    pub court: Option<String>,
+}
+
+impl fmt::Display for Citation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.square_brackets {
+            write!(f, "[{}] ", self.year)?;
+        } else {
+            write!(f, "({}) ", self.year)?;
+        }
+
+        if self.series == "LPELR" {
+            write!(f, "LPELR-{}", self.page)?;
+            if let Some(court) = &self.court {
+                write!(f, "({})", court)?;
+            }
+            return Ok(());
+        }
+
+        if let Some(volume) = &self.volume {
+            write!(f, "{} ", volume)?;
+        }
+        write!(f, "{} ", self.series)?;
+        if let Some(part) = &self.part {
+            write!(f, "(Pt. {}) ", part)?;
+        }
+        write!(f, "{}", self.page)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(String),
+    Word(String),
+}
+
+/// Parses a law report citation, tolerating the usual variants: missing or
+/// extra spaces, `Pt.475` / `Pt 475` / `Part 475`, lower case, a year without
+/// brackets, `(Pt. II)` as well as `(Pt. 475)`, and `LPELR-20385(SC)` /
+/// `LPELR 20385 SC`. The series must be one we know, or a short abbreviation
+/// in capitals such as "NILR".
+pub fn parse_citation(input: &str) -> Result<Citation, &'static str> {
+    let input = input.trim();
+    let (year, square_brackets, rest) = parse_year(input)?;
+    let mut tokens = tokenize(rest).into_iter().peekable();
+
+    let mut volume = None;
+    if let Some(Token::Number(n)) = tokens.peek() {
+        volume = Some(n.clone());
+        tokens.next();
+    }
+
+    let mut series_words = Vec::new();
+    while let Some(Token::Word(word)) = tokens.peek() {
+        if is_part_marker(word) && !series_words.is_empty() {
+            break;
+        }
+        series_words.push(word.clone());
+        tokens.next();
+    }
+    if series_words.is_empty() {
+        return Err("Missing the law report series (e.g. NWLR, SC, All NLR)");
+    }
+    // Neutral citations such as "[1854] EWHC J70" put a letter before the number.
+    let mut page_prefix = None;
+    if series_words.len() > 1
+        && series_words.last().is_some_and(|w| w.chars().count() == 1)
+        && known_series(&series_words[..series_words.len() - 1]).is_some()
+    {
+        page_prefix = series_words.pop();
+    }
+    let series = match known_series(&series_words) {
+        Some(series) => series.to_string(),
+        None => match series_words.as_slice() {
+            [word] if is_abbreviation(word) => word.clone(),
+            _ => return Err("Unknown law report series"),
+        },
+    };
+
+    let remaining: Vec<Token> = tokens.collect();
+
+    if series == "LPELR" {
+        return parse_lpelr(year, square_brackets, volume, &remaining);
+    }
+
+    let mut part = None;
+    let mut rest = remaining.as_slice();
+    if let [Token::Word(marker), part_token, tail @ ..] = rest
+        && is_part_marker(marker)
+    {
+        part = Some(parse_part(part_token)?);
+        rest = tail;
+    } else if PART_SERIES.contains(&series.as_str())
+        && let [Token::Number(n), tail @ ..] = rest
+        && matches!(tail.first(), Some(Token::Number(_)))
+    {
+        // "(1996) 9 NWLR 475 710": the part written without "Pt".
+        part = Some(parse_part(&Token::Number(n.clone()))?);
+        rest = tail;
+    }
+
+    let page = match rest {
+        [Token::Number(page)] => format!("{}{}", page_prefix.unwrap_or_default(), page),
+        [] => return Err("Missing the page number"),
+        _ => return Err("Unexpected text after the page number"),
+    };
+    if page.contains('-') {
+        return Err("The page must be a single number");
+    }
+
+    if PART_SERIES.contains(&series.as_str()) {
+        if volume.is_none() {
+            return Err("NWLR and FWLR citations need a volume number");
+        }
+        if part.is_none() {
+            return Err("NWLR and FWLR citations need a part number, e.g. (Pt. 475)");
+        }
+    }
+
+    Ok(Citation {
+        year,
+        square_brackets,
+        volume,
+        series,
+        part,
+        page,
+        court: None,
+    })
+}
+
+/// The canonical spelling of a citation, or an explanation of why it could
+/// not be read.
+pub fn normalize_citation(input: &str) -> Result<String, &'static str> {
+    parse_citation(input).map(|c| c.to_string())
+}
+
+/// The runs of letters and digits, lower-cased and joined with "|": the form
+/// `cases.citation_key` is generated in, so spacing and punctuation never stop
+/// a match but the boundaries between numbers are kept.
+pub fn citation_key(citation: &str) -> String {
+    let mut key = String::new();
+    let mut previous: Option<char> = None;
+    for c in citation.chars().map(|c| c.to_ascii_lowercase()) {
+        if !c.is_ascii_alphanumeric() {
+            previous = None;
+            continue;
+        }
+        let same_run = previous.is_some_and(|p| p.is_ascii_digit() == c.is_ascii_digit());
+        if !same_run && !key.is_empty() {
+            key.push('|');
+        }
+        key.push(c);
+        previous = Some(c);
+    }
+    key
+}
+
+fn parse_year(input: &str) -> Result<(i32, bool, &str), &'static str> {
+    let (square, body) = match input.chars().next() {
+        Some('(') => (false, &input[1..]),
+        Some('[') => (true, &input[1..]),
+        _ => (false, input),
+    };
+    let body = body.trim_start();
+
+    let digits = body.chars().take_while(char::is_ascii_digit).count();
+    if digits != 4 {
+        return Err("A citation starts with a four-digit year, e.g. (1996)");
+    }
+    let year: i32 = body[..digits].parse().map_err(|_| "Invalid year")?;
+    if !(1800..=2100).contains(&year) {
+        return Err("The year is out of range");
+    }
+
+    let mut rest = body[digits..].trim_start();
+    let close = if square { ']' } else { ')' };
+    if input.starts_with(['(', '[']) {
+        rest = rest
+            .strip_prefix(close)
+            .ok_or("The year's bracket is not closed")?;
+    }
+
+    Ok((year, square, rest))
+}
+
+fn parse_lpelr(
+    year: i32,
+    square_brackets: bool,
+    volume: Option<String>,
+    remaining: &[Token],
+) -> Result<Citation, &'static str> {
+    if volume.is_some() {
+        return Err("LPELR citations have no volume number");
+    }
+    let (page, court) = match remaining {
+        [Token::Number(n)] => (n.clone(), None),
+        [Token::Number(n), Token::Word(court)] => (n.clone(), Some(court.to_uppercase())),
+        [] => return Err("Missing the LPELR document number"),
+        _ => return Err("Unexpected text in the LPELR citation"),
+    };
+
+    Ok(Citation {
+        year,
+        square_brackets,
+        volume: None,
+        series: "LPELR".to_string(),
+        part: None,
+        page,
+        court,
+    })
+}
+
+// Splits into runs of digits (keeping ranges like "8-11") and runs of
+// letters; everything else separates tokens.
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let mut number: String = chars[start..i].iter().collect();
+
+            // "8-11" or "8 - 11"
+            let mut j = i;
+            while j < chars.len() && chars[j] == ' ' {
+                j += 1;
+            }
+            if j < chars.len() && matches!(chars[j], '-' | '–') {
+                j += 1;
+                while j < chars.len() && chars[j] == ' ' {
+                    j += 1;
+                }
+                let end_start = j;
+                while j < chars.len() && chars[j].is_ascii_digit() {
+                    j += 1;
+                }
+                if j > end_start {
+                    number.push('-');
+                    number.extend(&chars[end_start..j]);
+                    i = j;
+                }
+            }
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_alphabetic() {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+fn is_part_marker(word: &str) -> bool {
+    matches!(word.to_lowercase().as_str(), "pt" | "part" | "p")
+}
+
+// "475" or a Roman numeral such as "II", in its canonical spelling.
+fn parse_part(token: &Token) -> Result<String, &'static str> {
+    match token {
+        Token::Number(n) => n
+            .parse::<u32>()
+            .map(|n| n.to_string())
+            .map_err(|_| "Invalid part number"),
+        Token::Word(w) if w.chars().all(|c| "ivxlcdmIVXLCDM".contains(c)) => Ok(w.to_uppercase()),
+        Token::Word(_) => Err("Invalid part number"),
+    }
+}
+
+// A series we do not know is accepted only as written in law reports: one
+// short run of capitals, e.g. "NILR". Anything else is prose.
+fn is_abbreviation(word: &str) -> bool {
+    (2..=MAX_UNKNOWN_SERIES_CHARS).contains(&word.chars().count())
+        && word.chars().all(|c| c.is_ascii_uppercase())
+}
+
+fn known_series(words: &[String]) -> Option<&'static str> {
+    let key: String = words.concat().to_uppercase();
+    KNOWN_SERIES
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, name)| *name)
+}
+
+/// A citation found in running text, with its byte range.
+#[derive(Debug, Clone)]
+pub struct CitationMatch {
+    pub citation: Citation,
+    pub start: usize,
+    pub end: usize,
+}
+
+// Longest citation worth trying, from the year's bracket to the page.
+const MAX_CITATION_CHARS: usize = 80;
+
+/// Finds law report citations in a judgment. Only citations that open with
+/// a bracketed year and name a series in `KNOWN_SERIES` are recognised; a
+/// bare "1996" is too often just a year, and an unknown series too often
+/// prose.
+/// Each candidate is read up to the longest prefix that parses, so "(1996) 9
+/// NWLR (Pt. 475) 710 at 720" yields the citation without the pinpoint.
+pub fn find_citations(text: &str) -> Vec<CitationMatch> {
+    let mut found = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(offset) = text[search_from..].find(['(', '[']) {
+        let start = search_from + offset;
+        search_from = start + 1;
+
+        let after = &text[start + 1..];
+        if after.len() < 5
+            || !after.as_bytes()[..4].iter().all(u8::is_ascii_digit)
+            || !matches!(after.as_bytes()[4], b')' | b']')
+        {
+            continue;
+        }
+
+        let window_end = text[start..]
+            .char_indices()
+            .nth(MAX_CITATION_CHARS)
+            .map_or(text.len(), |(i, _)| start + i);
+        let window = &text[start..window_end];
+
+        // A citation ends on a page number or, for LPELR, on "(SC)".
+        let mut ends: Vec<usize> = window
+            .char_indices()
+            .filter(|(i, c)| {
+                let next = window[i + c.len_utf8()..].chars().next();
+                let prev = window[..*i].chars().next_back();
+                (c.is_ascii_digit() && !next.is_some_and(|n| n.is_ascii_digit()))
+                    || (*c == ')' && prev.is_some_and(char::is_alphabetic))
+            })
+            .map(|(i, c)| i + c.len_utf8())
+            .collect();
+        ends.reverse();
+
+        if let Some((end, citation)) = ends
+            .into_iter()
+            .find_map(|end| parse_citation(&window[..end]).ok().map(|c| (end, c)))
+            .filter(|(_, c)| KNOWN_SERIES.iter().any(|(_, name)| *name == c.series))
+        {
+            found.push(CitationMatch {
+                citation,
+                start,
+                end: start + end,
+            });
+            search_from = start + end;
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalized(input: &str) -> String {
+        normalize_citation(input).unwrap_or_else(|e| panic!("{:?}: {}", input, e))
+    }
+
+    #[test]
+    fn reads_the_usual_variants() {
+        let canonical = "(1996) 9 NWLR (Pt. 475) 710";
+        for input in [
+            "(1996) 9 NWLR (Pt. 475) 710",
+            "(1996)9NWLR(Pt.475)710",
+            "( 1996 )  9  NWLR  ( Pt. 475 )  710",
+            "(1996) 9 NWLR (Pt 475) 710",
+            "(1996) 9 NWLR (Part 475) 710",
+            "(1996) 9 nwlr pt 475 710",
+            "1996 9 NWLR Pt 475 710",
+            "(1996) 9 NWLR 475 710",
+        ] {
+            assert_eq!(normalized(input), canonical, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn reads_lpelr_citations() {
+        assert_eq!(
+            normalized("(2013) LPELR-20385(SC)"),
+            "(2013) LPELR-20385(SC)"
+        );
+        assert_eq!(
+            normalized("(2013) lpelr 20385 sc"),
+            "(2013) LPELR-20385(SC)"
+        );
+    }
+
+    #[test]
+    fn reads_square_bracket_and_multi_word_series() {
+        assert_eq!(normalized("[1932] AC 562"), "[1932] AC 562");
+        assert_eq!(normalized("(1962) 1 All N.L.R. 1"), "(1962) 1 All NLR 1");
+        assert_eq!(normalized("[1854] EWHC J70"), "[1854] EWHC J70");
+    }
+
+    #[test]
+    fn reads_roman_numeral_parts() {
+        assert_eq!(
+            normalized("(2005) 7 SC (Pt. II) 46"),
+            "(2005) 7 SC (Pt. II) 46"
+        );
+        assert_eq!(normalized("(2019) 1 SC (Pt. I) 1"), "(2019) 1 SC (Pt. I) 1");
+        assert_eq!(
+            parse_citation("(2005) 7 SC (pt ii) 46")
+                .unwrap()
+                .part
+                .as_deref(),
+            Some("II")
+        );
+    }
+
+    #[test]
+    fn accepts_unknown_series_written_as_an_abbreviation() {
+        assert_eq!(normalized("(1971) 1 NILR 12"), "(1971) 1 NILR 12");
+    }
+
+    #[test]
+    fn rejects_prose_and_unknown_series() {
+        for input in [
+            "(1999) the Supreme Court held at page 12",
+            "(1999) 3 Some Reports 12",
+            "(1999) 3 reports 12",
+            "(1999) 3 ABCDEFGH 12",
+        ] {
+            assert!(parse_citation(input).is_err(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn finds_citations_in_known_series_only() {
+        let text = "In (1999) the Supreme Court held at page 12 that, following \
+                    Donoghue [1932] AC 562 at 580 and (1971) 1 NILR 12, see \
+                    (1996) 9 NWLR (Pt. 475) 710.";
+        let found: Vec<String> = find_citations(text)
+            .iter()
+            .map(|m| m.citation.to_string())
+            .collect();
+        assert_eq!(found, ["[1932] AC 562", "(1996) 9 NWLR (Pt. 475) 710"]);
+    }
+
+    #[test]
+    fn citation_keys_ignore_spacing_and_punctuation() {
+        assert_eq!(
+            citation_key("(1996) 9 NWLR (Pt. 475) 710"),
+            "1996|9|nwlr|pt|475|710"
+        );
+        assert_eq!(
+            citation_key("(1996)9NWLR(Pt.475)710"),
+            citation_key("( 1996 ) 9 nwlr pt 475 710")
+        );
+        assert_eq!(citation_key("(1980) 8-11 SC 130"), "1980|8|11|sc|130");
+        assert_eq!(citation_key("[1854] EWHC J70"), "1854|ewhc|j|70");
+    }
+
+    #[test]
+    fn citation_keys_keep_numbers_apart() {
+        for (a, b) in [
+            ("(2001) 1 NWLR (Pt. 123) 45", "(2001) 1 NWLR (Pt. 12) 345"),
+            ("(2001) 11 NWLR (Pt. 1) 45", "(2001) 1 NWLR (Pt. 11) 45"),
+            ("(1981) 10 SC 1", "(1981) 1 SC 01"),
+            ("(2013) LPELR-20385(SC)", "(2013) LPELR-2038(5SC)"),
+        ] {
+            assert_ne!(citation_key(a), citation_key(b), "{:?} / {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn rejects_incomplete_citations() {
+        for input in [
+            "(1996) NWLR (Pt. 475) 710",
+            "(1996) 9 NWLR 710",
+            "(1996) 9 NWLR (Pt. 475)",
+            "(1996) 9 NWLR (Pt. X4) 710",
+            "(96) 9 NWLR (Pt. 475) 710",
+            "(2013) 5 LPELR-20385(SC)",
+        ] {
+            assert!(parse_citation(input).is_err(), "{:?}", input);
+        }
+    }
+}
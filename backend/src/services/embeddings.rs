@@ -0,0 +1,51 @@
+use crate::services::ai::{AiService, EmbeddingTask};
+use async_trait::async_trait;
+
+/// Dimension of `cases.embedding` in the init migration.
+pub const EMBEDDING_DIMENSIONS: usize = 768;
+
+/// Anything that can turn text into a vector for `cases.embedding`.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    async fn embed(
+        &self,
+        text: &str,
+        task: EmbeddingTask,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+#[async_trait]
+impl EmbeddingProvider for AiService {
+    async fn embed(
+        &self,
+        text: &str,
+        task: EmbeddingTask,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+        self.embed_text(text, task).await
+    }
+}
+
+/// Averages chunk embeddings into one document vector and rescales it to unit
+/// length, so cosine distance against query vectors stays meaningful.
+pub fn mean_pool(vectors: &[Vec<f32>]) -> Option<Vec<f32>> {
+    let dimensions = vectors.first()?.len();
+    let mut pooled = vec![0.0f32; dimensions];
+
+    for vector in vectors {
+        if vector.len() != dimensions {
+            return None;
+        }
+        for (sum, value) in pooled.iter_mut().zip(vector) {
+            *sum += value;
+        }
+    }
+
+    let norm = pooled.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in pooled.iter_mut() {
+            *value /= norm;
+        }
+    }
+
+    Some(pooled)
+}
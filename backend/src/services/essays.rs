@@ -0,0 +1,361 @@
+use crate::models::exams::{
+    AuthorityMark, ConclusionMark, Coverage, EssayAttempt, EssayMarking, EssayResult, IssueMark,
+    LibraryCase, Rubric,
+};
+use crate::services::ai::AiService;
+use crate::services::citation_graph::{KnownCase, extract_citations, find_name};
+use crate::services::citations::{citation_key, find_citations, normalize_citation};
+use serde_json::Value;
+use sqlx::PgPool;
+use sqlx::types::Json;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// Longer than any real exam answer; keeps the marking prompt bounded.
+const MAX_ANSWER_CHARS: usize = 20_000;
+// Caps on a rubric's marks, so its total always fits `max_score`.
+const MAX_ITEM_MARKS: u32 = 100;
+const MAX_RUBRIC_MARKS: u32 = 1_000;
+// Shorter case names ("A v B") match too much unrelated text.
+const MIN_CASE_NAME_CHARS: usize = 10;
+
+const ATTEMPT_COLUMNS: &str = "id, question_id, answer, score, max_score, marking, created_at";
+
+pub enum EssayError {
+    NotFound,
+    Invalid(&'static str),
+    Ai(String),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for EssayError {
+    fn from(e: sqlx::Error) -> Self {
+        EssayError::Database(e)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EssayQuestion {
+    question: String,
+    question_type: String,
+    correct_answer: String,
+    explanation: Option<String>,
+    rubric: Option<Value>,
+}
+
+/// Checks a rubric before it is saved: every issue and authority must carry
+/// marks, within `MAX_ITEM_MARKS` each and `MAX_RUBRIC_MARKS` in all, and
+/// case citations are stored in their standard form.
+pub fn validate_rubric(mut rubric: Rubric) -> Result<Rubric, &'static str> {
+    if rubric.issues.is_empty() {
+        return Err("A rubric needs at least one issue");
+    }
+    for issue in &mut rubric.issues {
+        issue.issue = issue.issue.trim().to_string();
+        if issue.issue.is_empty() || issue.marks == 0 {
+            return Err("Every issue needs a description and at least one mark");
+        }
+        if issue.marks > MAX_ITEM_MARKS {
+            return Err("An issue can carry at most 100 marks");
+        }
+    }
+    for authority in &mut rubric.authorities {
+        authority.name = authority.name.trim().to_string();
+        if authority.name.is_empty() || authority.marks == 0 {
+            return Err("Every authority needs a name and at least one mark");
+        }
+        if authority.marks > MAX_ITEM_MARKS {
+            return Err("An authority can carry at most 100 marks");
+        }
+        authority.citation = match authority.citation.as_deref().map(str::trim) {
+            Some(citation) if !citation.is_empty() => Some(normalize_citation(citation)?),
+            _ => None,
+        };
+    }
+    rubric.conclusion.conclusion = rubric.conclusion.conclusion.trim().to_string();
+    if rubric.conclusion.conclusion.is_empty() {
+        return Err("The rubric needs an expected conclusion");
+    }
+    if rubric.conclusion.marks > MAX_ITEM_MARKS {
+        return Err("The conclusion can carry at most 100 marks");
+    }
+    let total = rubric
+        .issues
+        .iter()
+        .map(|i| i.marks)
+        .chain(rubric.authorities.iter().map(|a| a.marks))
+        .fold(rubric.conclusion.marks, u32::saturating_add);
+    if total > MAX_RUBRIC_MARKS {
+        return Err("A rubric can carry at most 1,000 marks in all");
+    }
+
+    Ok(rubric)
+}
+
+/// Sets the marking rubric of an essay question.
+pub async fn set_rubric(
+    db: &PgPool,
+    question_id: Uuid,
+    rubric: Rubric,
+) -> Result<Rubric, EssayError> {
+    let rubric = validate_rubric(rubric).map_err(EssayError::Invalid)?;
+
+    let question_type =
+        sqlx::query_scalar::<_, String>("SELECT question_type FROM exam_questions WHERE id = $1")
+            .bind(question_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or(EssayError::NotFound)?;
+    if question_type != "essay" {
+        return Err(EssayError::Invalid("Only essay questions have a rubric"));
+    }
+
+    sqlx::query("UPDATE exam_questions SET rubric = $2 WHERE id = $1")
+        .bind(question_id)
+        .bind(Json(&rubric))
+        .execute(db)
+        .await?;
+
+    Ok(rubric)
+}
+
+/// Marks a student's answer to an essay question and records the attempt.
+/// The AI judges the issues and the conclusion; authorities are checked
+/// against the answer's text and the case library, so a citation only
+/// counts if the student actually wrote it, and suggested further reading
+/// is limited to cases we hold.
+pub async fn mark_essay(
+    db: &PgPool,
+    ai: &AiService,
+    user_id: Uuid,
+    question_id: Uuid,
+    answer: &str,
+) -> Result<EssayResult, EssayError> {
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err(EssayError::Invalid("Answer must not be empty"));
+    }
+    if answer.chars().count() > MAX_ANSWER_CHARS {
+        return Err(EssayError::Invalid(
+            "Answers are limited to 20,000 characters",
+        ));
+    }
+
+    let question = sqlx::query_as::<_, EssayQuestion>(
+        "SELECT question, question_type, correct_answer, explanation, rubric \
+         FROM exam_questions WHERE id = $1",
+    )
+    .bind(question_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(EssayError::NotFound)?;
+    if question.question_type != "essay" {
+        return Err(EssayError::Invalid(
+            "Only essay questions are marked against a rubric",
+        ));
+    }
+    let rubric: Rubric = question
+        .rubric
+        .and_then(|r| serde_json::from_value(r).ok())
+        .ok_or(EssayError::Invalid(
+            "This question has no marking rubric yet",
+        ))?;
+    // Checked again before paying for the AI call, in case it was saved
+    // under older rules.
+    let rubric = validate_rubric(rubric).map_err(EssayError::Invalid)?;
+
+    let assessment = ai
+        .assess_essay(
+            &question.question,
+            &question.correct_answer,
+            &rubric,
+            answer,
+        )
+        .await
+        .map_err(|e| EssayError::Ai(e.to_string()))?;
+
+    let cases = sqlx::query_as::<_, KnownCase>("SELECT id, title, citation_key FROM cases")
+        .fetch_all(db)
+        .await?;
+    let cited = extract_citations(Uuid::nil(), answer, &cases, &[]);
+    let cited_ids: Vec<Uuid> = cited.iter().filter_map(|c| c.cited_case_id).collect();
+    let cited_keys: HashSet<&str> = cited
+        .iter()
+        .filter_map(|c| c.cited_key.as_deref())
+        .collect();
+    let lowered = answer.to_ascii_lowercase();
+
+    let rubric_ids: Vec<Option<Uuid>> = rubric
+        .authorities
+        .iter()
+        .map(|a| {
+            a.citation
+                .as_deref()
+                .and_then(|c| resolve_authority(c, &cases))
+                .or_else(|| resolve_authority(&a.name, &cases))
+        })
+        .collect();
+    let mut further_ids = Vec::new();
+    for suggestion in &assessment.further_authorities {
+        if let Some(id) = resolve_authority(suggestion, &cases)
+            && !cited_ids.contains(&id)
+            && !rubric_ids.contains(&Some(id))
+            && !further_ids.contains(&id)
+        {
+            further_ids.push(id);
+        }
+    }
+
+    let wanted: Vec<Uuid> = cited_ids
+        .iter()
+        .copied()
+        .chain(rubric_ids.iter().flatten().copied())
+        .chain(further_ids.iter().copied())
+        .collect();
+    let library: HashMap<Uuid, LibraryCase> = sqlx::query_as::<_, LibraryCase>(
+        "SELECT id, title, citation, good_law_status FROM cases WHERE id = ANY($1)",
+    )
+    .bind(&wanted)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|c| (c.id, c))
+    .collect();
+
+    let issues: Vec<IssueMark> = rubric
+        .issues
+        .iter()
+        .enumerate()
+        .map(|(i, issue)| {
+            let judged = assessment.issues.iter().find(|a| a.number == i + 1);
+            let coverage = judged.map_or(Coverage::Missed, |a| a.coverage);
+            IssueMark {
+                issue: issue.issue.clone(),
+                marks: issue.marks,
+                awarded: coverage.award(issue.marks),
+                coverage,
+                comment: judged.map(|a| a.comment.clone()).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    let authorities: Vec<AuthorityMark> = rubric
+        .authorities
+        .iter()
+        .zip(&rubric_ids)
+        .map(|(authority, case_id)| {
+            let was_cited = case_id.is_some_and(|id| cited_ids.contains(&id))
+                || authority
+                    .citation
+                    .as_deref()
+                    .is_some_and(|c| cited_keys.contains(citation_key(c).as_str()))
+                || find_name(&lowered, &authority.name).is_some();
+            AuthorityMark {
+                name: authority.name.clone(),
+                citation: authority.citation.clone(),
+                marks: authority.marks,
+                awarded: if was_cited {
+                    authority.marks as f64
+                } else {
+                    0.0
+                },
+                cited: was_cited,
+                case: case_id.and_then(|id| library.get(&id).cloned()),
+            }
+        })
+        .collect();
+
+    let conclusion = ConclusionMark {
+        conclusion: rubric.conclusion.conclusion.clone(),
+        marks: rubric.conclusion.marks,
+        awarded: assessment.conclusion.award(rubric.conclusion.marks),
+        coverage: assessment.conclusion,
+        comment: assessment.conclusion_comment,
+    };
+
+    let score = issues.iter().map(|i| i.awarded).sum::<f64>()
+        + authorities.iter().map(|a| a.awarded).sum::<f64>()
+        + conclusion.awarded;
+    let marking = EssayMarking {
+        missed_issues: issues
+            .iter()
+            .filter(|i| i.coverage == Coverage::Missed)
+            .map(|i| i.issue.clone())
+            .collect(),
+        missed_authorities: authorities.iter().filter(|a| !a.cited).cloned().collect(),
+        further_authorities: further_ids
+            .iter()
+            .filter_map(|id| library.get(id).cloned())
+            .collect(),
+        cited_cases: cited_ids
+            .iter()
+            .filter_map(|id| library.get(id).cloned())
+            .collect(),
+        feedback: assessment.feedback,
+        issues,
+        authorities,
+        conclusion,
+    };
+
+    let attempt = sqlx::query_as::<_, EssayAttempt>(&format!(
+        "INSERT INTO essay_attempts (user_id, question_id, answer, score, max_score, marking) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING {}",
+        ATTEMPT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(question_id)
+    .bind(answer)
+    .bind(score)
+    .bind(rubric.max_score() as i32)
+    .bind(Json(&marking))
+    .fetch_one(db)
+    .await?;
+
+    Ok(EssayResult {
+        attempt,
+        model_answer: question.correct_answer,
+        explanation: question.explanation,
+    })
+}
+
+// The library case a free-text authority refers to: by reported citation,
+// else by a case name ("Donoghue v Stevenson") appearing in the text.
+fn resolve_authority(text: &str, cases: &[KnownCase]) -> Option<Uuid> {
+    for m in find_citations(text) {
+        let key = citation_key(&m.citation.to_string());
+        if let Some(case) = cases.iter().find(|c| c.citation_key == key) {
+            return Some(case.id);
+        }
+    }
+
+    let lowered = text.to_ascii_lowercase();
+    cases
+        .iter()
+        .find(|c| {
+            let name = c.title.trim();
+            name.eq_ignore_ascii_case(text.trim())
+                || (name.chars().count() >= MIN_CASE_NAME_CHARS
+                    && name.to_ascii_lowercase().contains(" v ")
+                    && find_name(&lowered, name).is_some())
+        })
+        .map(|c| c.id)
+}
+
+/// A user's attempts at one essay question, newest first.
+pub async fn list_attempts(
+    db: &PgPool,
+    user_id: Uuid,
+    question_id: Uuid,
+    limit: i64,
+) -> Result<Vec<EssayAttempt>, sqlx::Error> {
+    sqlx::query_as::<_, EssayAttempt>(&format!(
+        "SELECT {} FROM essay_attempts WHERE user_id = $1 AND question_id = $2 \
+         ORDER BY created_at DESC LIMIT $3",
+        ATTEMPT_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(question_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
@@ -0,0 +1,101 @@
+use crate::models::exams::AnswerKey;
+use serde_json::Value;
+
+/// Marks an answer against its key. Multiple-choice answers may be given as
+/// the option text or its letter ("A" for the first option); text is
+/// compared ignoring case and surrounding whitespace.
+pub fn is_correct(key: &AnswerKey, answer: &str) -> bool {
+    let answer = answer.trim();
+    let expected = key.correct_answer.trim();
+    if answer.eq_ignore_ascii_case(expected) {
+        return true;
+    }
+
+    let options: Vec<&str> = key
+        .options
+        .as_ref()
+        .and_then(Value::as_array)
+        .map(|options| options.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut letter = answer.chars();
+    match (letter.next(), letter.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => {
+            let index = (c.to_ascii_uppercase() as u8 - b'A') as usize;
+            options
+                .get(index)
+                .is_some_and(|option| option.trim().eq_ignore_ascii_case(expected))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn multiple_choice(correct: &str) -> AnswerKey {
+        AnswerKey {
+            question_type: "multiple_choice".to_string(),
+            options: Some(json!([
+                "Section 33",
+                "Section 35",
+                "Section 36",
+                "Section 40"
+            ])),
+            correct_answer: correct.to_string(),
+            explanation: None,
+        }
+    }
+
+    #[test]
+    fn accepts_the_option_text_in_any_case() {
+        let key = multiple_choice("Section 36");
+        assert!(is_correct(&key, "Section 36"));
+        assert!(is_correct(&key, "  section 36 "));
+        assert!(!is_correct(&key, "Section 35"));
+    }
+
+    #[test]
+    fn accepts_the_option_letter() {
+        let key = multiple_choice("Section 36");
+        assert!(is_correct(&key, "C"));
+        assert!(is_correct(&key, " c "));
+        assert!(!is_correct(&key, "B"));
+        // Out of range, or not a letter.
+        assert!(!is_correct(&key, "E"));
+        assert!(!is_correct(&key, "3"));
+        assert!(!is_correct(&key, "CC"));
+    }
+
+    #[test]
+    fn marks_empty_answers_wrong() {
+        assert!(!is_correct(&multiple_choice("Section 36"), ""));
+        assert!(!is_correct(&multiple_choice("Section 36"), "   "));
+    }
+
+    #[test]
+    fn compares_questions_without_options_on_text() {
+        let key = AnswerKey {
+            question_type: "short_answer".to_string(),
+            options: None,
+            correct_answer: "Donoghue v Stevenson".to_string(),
+            explanation: None,
+        };
+        assert!(is_correct(&key, "donoghue v stevenson"));
+        assert!(!is_correct(&key, "A"));
+    }
+
+    #[test]
+    fn a_letter_that_is_the_answer_counts() {
+        let key = AnswerKey {
+            question_type: "multiple_choice".to_string(),
+            options: Some(json!(["A", "B"])),
+            correct_answer: "B".to_string(),
+            explanation: None,
+        };
+        assert!(is_correct(&key, "b"));
+        assert!(!is_correct(&key, "a"));
+    }
+}
@@ -0,0 +1,317 @@
+use crate::services::llm::{
+    ApiError, ChatRole, EmbeddingTask, LlmProvider, LlmRequest, TextStream, sse_data,
+};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+
+#[derive(Serialize)]
+struct GeminiPart {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct GeminiContent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+    parts: Vec<GeminiPart>,
+}
+
+impl GeminiContent {
+    fn text(role: Option<&'static str>, text: &str) -> Self {
+        Self {
+            role,
+            parts: vec![GeminiPart {
+                text: text.to_string(),
+            }],
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    response_mime_type: &'static str,
+    response_schema: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GeminiGenerationConfig>,
+}
+
+/// Gemini's `responseSchema` is an OpenAPI subset that spells types in
+/// upper case (`OBJECT`, `STRING`, ...).
+fn gemini_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| match (key.as_str(), value) {
+                    ("type", Value::String(t)) => (key.clone(), Value::String(t.to_uppercase())),
+                    _ => (key.clone(), gemini_schema(value)),
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(gemini_schema).collect()),
+        other => other.clone(),
+    }
+}
+
+impl GeminiRequest {
+    fn from_llm(request: &LlmRequest) -> Self {
+        let mut contents: Vec<GeminiContent> = Vec::with_capacity(request.turns.len());
+
+        // Gemini expects turns to alternate, so consecutive messages from the
+        // same side (e.g. a question whose answer failed to save) are merged.
+        for turn in &request.turns {
+            let role = match turn.role {
+                ChatRole::User => "user",
+                ChatRole::Assistant => "model",
+            };
+            match contents.last_mut() {
+                Some(last) if last.role == Some(role) => last.parts.push(GeminiPart {
+                    text: turn.content.clone(),
+                }),
+                _ => contents.push(GeminiContent::text(Some(role), &turn.content)),
+            }
+        }
+
+        Self {
+            contents,
+            system_instruction: request
+                .system
+                .as_deref()
+                .map(|s| GeminiContent::text(None, s)),
+            generation_config: request.response_schema.as_ref().map(|schema| {
+                GeminiGenerationConfig {
+                    response_mime_type: "application/json",
+                    response_schema: gemini_schema(schema),
+                }
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GeminiResponsePart {
+    text: String,
+}
+
+#[derive(Deserialize, Default)]
+struct GeminiResponseContent {
+    #[serde(default)]
+    parts: Vec<GeminiResponsePart>,
+}
+
+#[derive(Deserialize)]
+struct GeminiCandidate {
+    // The final streamed chunk can carry only a finishReason and no content.
+    #[serde(default)]
+    content: GeminiResponseContent,
+}
+
+#[derive(Deserialize)]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+}
+
+impl GeminiResponse {
+    fn text(&self) -> String {
+        self.candidates
+            .first()
+            .map(|c| c.content.parts.iter().map(|p| p.text.as_str()).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiEmbedRequest {
+    model: String,
+    content: GeminiContent,
+    task_type: &'static str,
+}
+
+#[derive(Deserialize)]
+struct GeminiEmbedding {
+    values: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct GeminiEmbedResponse {
+    embedding: GeminiEmbedding,
+}
+
+/// Google's Generative Language REST API.
+pub struct GeminiProvider {
+    client: Client,
+    api_key: String,
+    model: String,
+    embedding_model: String,
+}
+
+impl GeminiProvider {
+    pub fn from_env(client: Client) -> Self {
+        let api_key = env::var("GEMINI_API_KEY").unwrap_or_else(|_| "".to_string());
+        // Default to gemini-1.5-flash for speed and cost-effectiveness
+        let model = env::var("GEMINI_MODEL").unwrap_or_else(|_| "gemini-1.5-flash".to_string());
+        // text-embedding-004 returns 768 dimensions, matching cases.embedding
+        let embedding_model =
+            env::var("GEMINI_EMBEDDING_MODEL").unwrap_or_else(|_| "text-embedding-004".to_string());
+
+        Self {
+            client,
+            api_key,
+            model,
+            embedding_model,
+        }
+    }
+
+    // The key goes in the `x-goog-api-key` header, never the URL, which
+    // reqwest includes in its errors.
+    fn url(&self, model: &str, method: &str) -> String {
+        format!(
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:{}",
+            model, method
+        )
+    }
+
+    async fn post<T: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &T,
+    ) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+        if self.api_key.is_empty() {
+            return Err("GEMINI_API_KEY is not set".into());
+        }
+
+        let res = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("x-goog-api-key", &self.api_key)
+            .json(body)
+            .send()
+            .await?;
+
+        if res.status().is_success() {
+            Ok(res)
+        } else {
+            let status = res.status();
+            let error_text = res.text().await?;
+            Err(ApiError {
+                provider: "Gemini",
+                status,
+                body: error_text,
+            }
+            .into())
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for GeminiProvider {
+    async fn generate(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let url = self.url(&self.model, "generateContent");
+        let res = self.post(&url, &GeminiRequest::from_llm(request)).await?;
+
+        let parsed: GeminiResponse = res.json().await?;
+        if parsed
+            .candidates
+            .first()
+            .is_some_and(|c| !c.content.parts.is_empty())
+        {
+            return Ok(parsed.text());
+        }
+        Err("Unexpected response structure from Gemini API".into())
+    }
+
+    async fn stream(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<TextStream, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}?alt=sse", self.url(&self.model, "streamGenerateContent"));
+        let res = self.post(&url, &GeminiRequest::from_llm(request)).await?;
+
+        // With alt=sse every event is a `data: {GeminiResponse}` line.
+        let stream = sse_data(res).filter_map(|data| async move {
+            match data {
+                Ok(data) => match serde_json::from_str::<GeminiResponse>(&data) {
+                    Ok(parsed) => {
+                        let text = parsed.text();
+                        (!text.is_empty()).then_some(Ok(text))
+                    }
+                    Err(e) => Some(Err(e.into())),
+                },
+                Err(e) => Some(Err(e)),
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn embed(
+        &self,
+        text: &str,
+        task: EmbeddingTask,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+        let req_body = GeminiEmbedRequest {
+            model: format!("models/{}", self.embedding_model),
+            content: GeminiContent::text(None, text),
+            task_type: match task {
+                EmbeddingTask::Query => "RETRIEVAL_QUERY",
+                EmbeddingTask::Document => "RETRIEVAL_DOCUMENT",
+            },
+        };
+
+        let url = self.url(&self.embedding_model, "embedContent");
+        let res = self.post(&url, &req_body).await?;
+
+        let parsed: GeminiEmbedResponse = res.json().await?;
+        Ok(parsed.embedding.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn schema_types_are_uppercased_at_every_level() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "issues": { "type": "array", "items": { "type": "string" } },
+                "coverage": { "type": "string", "enum": ["full", "partial"] },
+                "type": { "type": "integer" }
+            },
+            "required": ["issues", "type"]
+        });
+
+        assert_eq!(
+            gemini_schema(&schema),
+            json!({
+                "type": "OBJECT",
+                "properties": {
+                    "issues": { "type": "ARRAY", "items": { "type": "STRING" } },
+                    "coverage": { "type": "STRING", "enum": ["full", "partial"] },
+                    "type": { "type": "INTEGER" }
+                },
+                "required": ["issues", "type"]
+            })
+        );
+    }
+}
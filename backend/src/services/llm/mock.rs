@@ -0,0 +1,236 @@
+use crate::services::embeddings::EMBEDDING_DIMENSIONS;
+use crate::services::llm::{ChatRole, EmbeddingTask, LlmProvider, LlmRequest, TextStream};
+use async_trait::async_trait;
+use serde_json::Value;
+
+/// Deterministic, network-free provider for offline development and tests.
+/// Replies echo the latest user turn, and embeddings are hashed
+/// bag-of-words vectors, so texts sharing words land close together.
+pub struct MockProvider;
+
+impl MockProvider {
+    fn reply(request: &LlmRequest) -> String {
+        if let Some(schema) = &request.response_schema {
+            return sample(schema).to_string();
+        }
+
+        let question = request
+            .turns
+            .iter()
+            .rev()
+            .find(|t| t.role == ChatRole::User)
+            .map(|t| t.content.as_str())
+            .unwrap_or_default();
+        let preview: String = question.chars().take(200).collect();
+        format!(
+            "[mock] LEXA received {} earlier turn(s). Latest question: {}",
+            request.turns.len().saturating_sub(1),
+            preview
+        )
+    }
+}
+
+/// Smallest document that satisfies `schema`, so JSON-mode callers get
+/// something they can parse.
+fn sample(schema: &Value) -> Value {
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|v| v.first())
+    {
+        return first.clone();
+    }
+
+    match schema.get("type").and_then(Value::as_str) {
+        Some("object") => Value::Object(
+            schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .map(|props| {
+                    props
+                        .iter()
+                        .map(|(key, prop)| (key.clone(), sample(prop)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        ),
+        Some("array") => Value::Array(
+            schema
+                .get("items")
+                .map(|items| vec![sample(items)])
+                .unwrap_or_default(),
+        ),
+        Some("string") => Value::String("[mock]".to_string()),
+        Some("integer") | Some("number") => Value::from(0),
+        Some("boolean") => Value::Bool(false),
+        _ => Value::Null,
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockProvider {
+    async fn generate(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::reply(request))
+    }
+
+    async fn stream(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<TextStream, Box<dyn std::error::Error + Send + Sync>> {
+        let words: Vec<Result<String, Box<dyn std::error::Error + Send + Sync>>> =
+            Self::reply(request)
+                .split_inclusive(' ')
+                .map(|w| Ok(w.to_string()))
+                .collect();
+        Ok(Box::pin(futures_util::stream::iter(words)))
+    }
+
+    async fn embed(
+        &self,
+        text: &str,
+        _task: EmbeddingTask,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut vector = vec![0.0f32; EMBEDDING_DIMENSIONS];
+
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            // FNV-1a, so the same word always lands in the same bucket.
+            let mut hash: u64 = 0xcbf29ce484222325;
+            for byte in word.to_lowercase().bytes() {
+                hash ^= byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+            vector[(hash % EMBEDDING_DIMENSIONS as u64) as usize] += 1.0;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|v| *v /= norm);
+        }
+
+        Ok(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::llm::ChatTurn;
+    use futures_util::StreamExt;
+    use serde_json::json;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn samples_satisfy_the_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "issues": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "number": { "type": "integer" },
+                            "coverage": { "type": "string", "enum": ["full", "partial", "missed"] }
+                        }
+                    }
+                },
+                "feedback": { "type": "string" },
+                "passed": { "type": "boolean" }
+            }
+        });
+
+        assert_eq!(
+            sample(&schema),
+            json!({
+                "issues": [{ "number": 0, "coverage": "full" }],
+                "feedback": "[mock]",
+                "passed": false
+            })
+        );
+        assert_eq!(sample(&json!({})), Value::Null);
+    }
+
+    #[tokio::test]
+    async fn replies_echo_the_latest_question() {
+        let request = LlmRequest {
+            system: Some("Be brief.".to_string()),
+            turns: vec![
+                ChatTurn {
+                    role: ChatRole::User,
+                    content: "What is consideration?".to_string(),
+                },
+                ChatTurn {
+                    role: ChatRole::Assistant,
+                    content: "Something of value.".to_string(),
+                },
+                ChatTurn {
+                    role: ChatRole::User,
+                    content: "And estoppel?".to_string(),
+                },
+            ],
+            response_schema: None,
+        };
+
+        let reply = MockProvider.generate(&request).await.unwrap();
+        assert_eq!(
+            reply,
+            "[mock] LEXA received 2 earlier turn(s). Latest question: And estoppel?"
+        );
+
+        let streamed: Vec<String> = MockProvider
+            .stream(&request)
+            .await
+            .unwrap()
+            .map(|word| word.unwrap())
+            .collect()
+            .await;
+        assert!(streamed.len() > 1);
+        assert_eq!(streamed.concat(), reply);
+    }
+
+    #[tokio::test]
+    async fn json_requests_get_a_parseable_reply() {
+        let mut request = LlmRequest::prompt("Mark this answer");
+        request.response_schema = Some(json!({
+            "type": "object",
+            "properties": { "score": { "type": "number" } }
+        }));
+
+        let reply = MockProvider.generate(&request).await.unwrap();
+        let parsed: Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(parsed, json!({ "score": 0 }));
+    }
+
+    #[tokio::test]
+    async fn embeddings_are_deterministic_unit_vectors() {
+        let embed = |text| MockProvider.embed(text, EmbeddingTask::Document);
+        let first = embed("Breach of contract damages").await.unwrap();
+        let again = embed("breach of CONTRACT damages").await.unwrap();
+
+        assert_eq!(first.len(), EMBEDDING_DIMENSIONS);
+        assert_eq!(first, again);
+        assert!((cosine(&first, &first) - 1.0).abs() < 1e-5);
+        assert!(embed("").await.unwrap().iter().all(|v| *v == 0.0));
+    }
+
+    #[tokio::test]
+    async fn shared_words_embed_closer_together() {
+        let embed = |text| MockProvider.embed(text, EmbeddingTask::Query);
+        let query = embed("damages for breach of contract").await.unwrap();
+        let related = embed("breach of contract and the measure of damages")
+            .await
+            .unwrap();
+        let unrelated = embed("murder requires malice aforethought").await.unwrap();
+
+        assert!(cosine(&query, &related) > cosine(&query, &unrelated));
+    }
+}
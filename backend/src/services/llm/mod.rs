@@ -0,0 +1,215 @@
+pub mod gemini;
+pub mod mock;
+pub mod openai;
+
+use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
+use reqwest::{Client, StatusCode};
+use serde_json::Value;
+use std::env;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Text fragments of a model reply, in the order they were generated.
+pub type TextStream =
+    Pin<Box<dyn Stream<Item = Result<String, Box<dyn std::error::Error + Send + Sync>>> + Send>>;
+
+/// Embedding models can optimise differently for search queries and the
+/// documents being searched, so callers say which side they are on.
+#[derive(Debug, Clone, Copy)]
+pub enum EmbeddingTask {
+    Query,
+    Document,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+impl ChatRole {
+    /// Maps a `chat_messages.role` value; older rows used 'lexa' for replies.
+    pub fn from_db(role: &str) -> Self {
+        match role {
+            "assistant" | "lexa" => ChatRole::Assistant,
+            _ => ChatRole::User,
+        }
+    }
+}
+
+/// One message in a conversation sent to the model.
+#[derive(Debug, Clone)]
+pub struct ChatTurn {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+/// A provider-neutral generation request: an optional system instruction
+/// followed by the conversation, ending with the user's latest turn.
+#[derive(Debug, Clone)]
+pub struct LlmRequest {
+    pub system: Option<String>,
+    pub turns: Vec<ChatTurn>,
+    /// When set, the reply must be a JSON document matching this JSON Schema
+    /// (lowercase `type` names, no `$ref`).
+    pub response_schema: Option<Value>,
+}
+
+impl LlmRequest {
+    pub fn prompt(prompt: &str) -> Self {
+        Self {
+            system: None,
+            turns: vec![ChatTurn {
+                role: ChatRole::User,
+                content: prompt.to_string(),
+            }],
+            response_schema: None,
+        }
+    }
+
+    pub fn with_schema(mut self, schema: Value) -> Self {
+        self.response_schema = Some(schema);
+        self
+    }
+}
+
+/// A model backend that `AiService` can talk to.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    async fn generate(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn stream(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<TextStream, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn embed(
+        &self,
+        text: &str,
+        task: EmbeddingTask,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// A non-success HTTP response from a provider's API.
+#[derive(Debug)]
+pub struct ApiError {
+    pub provider: &'static str,
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl ApiError {
+    /// Rate limiting and server-side failures are worth another attempt;
+    /// other client errors (bad key, bad request) will fail the same way again.
+    pub fn is_retryable(&self) -> bool {
+        self.status == StatusCode::TOO_MANY_REQUESTS || self.status.is_server_error()
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} API error: {} - {}",
+            self.provider, self.status, self.body
+        )
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Picks the provider named by `LLM_PROVIDER`: `gemini` (default), `openai`
+/// for any OpenAI-compatible server (OpenAI, llama.cpp, Ollama, vLLM), or
+/// `mock` for offline development. HTTP providers send through `client`.
+pub fn provider_from_env(client: Client) -> Arc<dyn LlmProvider> {
+    match env::var("LLM_PROVIDER")
+        .unwrap_or_default()
+        .to_lowercase()
+        .as_str()
+    {
+        "openai" => Arc::new(openai::OpenAiCompatibleProvider::from_env(client)),
+        "mock" => Arc::new(mock::MockProvider),
+        "" | "gemini" => Arc::new(gemini::GeminiProvider::from_env(client)),
+        other => {
+            eprintln!("Unknown LLM_PROVIDER '{}', falling back to gemini", other);
+            Arc::new(gemini::GeminiProvider::from_env(client))
+        }
+    }
+}
+
+/// Yields the payload of each `data:` line in a Server-Sent Events response.
+/// Bytes are buffered until a full line arrives so multi-byte characters split
+/// across network chunks are decoded intact.
+pub fn sse_data(
+    res: reqwest::Response,
+) -> impl Stream<Item = Result<String, Box<dyn std::error::Error + Send + Sync>>> + Send {
+    futures_util::stream::unfold(
+        (res.bytes_stream(), Vec::<u8>::new()),
+        |(mut bytes, mut buffer)| async move {
+            loop {
+                if let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    if let Some(data) = line.trim().strip_prefix("data:") {
+                        return Some((Ok(data.trim().to_string()), (bytes, buffer)));
+                    }
+                    continue;
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => return Some((Err(e.into()), (bytes, buffer))),
+                    None => return None,
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(chunks: &[&'static [u8]]) -> reqwest::Response {
+        let chunks: Vec<Result<&'static [u8], std::io::Error>> =
+            chunks.iter().map(|c| Ok(*c)).collect();
+        let body = reqwest::Body::wrap_stream(futures_util::stream::iter(chunks));
+        reqwest::Response::from(axum::http::Response::new(body))
+    }
+
+    async fn events(chunks: &[&'static [u8]]) -> Vec<String> {
+        sse_data(response(chunks))
+            .map(|data| data.unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn yields_each_data_line() {
+        let body: &[&[u8]] = &[b"event: message\ndata: {\"a\":1}\n\ndata:[DONE]\n"];
+        assert_eq!(events(body).await, ["{\"a\":1}", "[DONE]"]);
+    }
+
+    #[tokio::test]
+    async fn joins_lines_split_across_chunks() {
+        // "é" is 0xC3 0xA9; the chunk boundary falls between its two bytes.
+        let body: &[&[u8]] = &[b"data: caf\xC3", b"\xA9 au\r\n", b"data: lait\n"];
+        assert_eq!(events(body).await, ["café au", "lait"]);
+    }
+
+    #[tokio::test]
+    async fn drops_an_unterminated_last_line() {
+        let body: &[&[u8]] = &[b"data: first\ndata: cut off"];
+        assert_eq!(events(body).await, ["first"]);
+    }
+
+    #[tokio::test]
+    async fn empty_body_has_no_events() {
+        assert!(events(&[]).await.is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+use crate::services::llm::{
+    ApiError, ChatRole, EmbeddingTask, LlmProvider, LlmRequest, TextStream, sse_data,
+};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::env;
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+}
+
+impl<'a> ChatCompletionRequest<'a> {
+    fn from_llm(model: &'a str, request: &'a LlmRequest, stream: bool) -> Self {
+        let mut messages = Vec::with_capacity(request.turns.len() + 1);
+        if let Some(system) = &request.system {
+            messages.push(ChatMessage {
+                role: "system",
+                content: system,
+            });
+        }
+        messages.extend(request.turns.iter().map(|turn| ChatMessage {
+            role: match turn.role {
+                ChatRole::User => "user",
+                ChatRole::Assistant => "assistant",
+            },
+            content: &turn.content,
+        }));
+
+        Self {
+            model,
+            messages,
+            stream,
+            response_format: request.response_schema.as_ref().map(|schema| {
+                json!({
+                    "type": "json_schema",
+                    "json_schema": { "name": "response", "schema": schema },
+                })
+            }),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    // Non-streaming responses carry `message`, streamed chunks carry `delta`.
+    #[serde(alias = "delta")]
+    message: ResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionResponse {
+    #[serde(default)]
+    choices: Vec<Choice>,
+}
+
+impl ChatCompletionResponse {
+    fn text(self) -> Option<String> {
+        self.choices.into_iter().next()?.message.content
+    }
+}
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+/// Any server speaking the OpenAI chat completions and embeddings API:
+/// OpenAI itself, or a local llama.cpp, Ollama or vLLM server.
+pub struct OpenAiCompatibleProvider {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    embedding_model: String,
+    embedding_dimensions: Option<usize>,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn from_env(client: Client) -> Self {
+        // e.g. http://localhost:11434/v1 for Ollama, http://localhost:8080/v1 for llama.cpp
+        let base_url = env::var("OPENAI_BASE_URL")
+            .unwrap_or_else(|_| "https://api.openai.com/v1".to_string())
+            .trim_end_matches('/')
+            .to_string();
+        // Local servers usually need no key.
+        let api_key = env::var("OPENAI_API_KEY").ok().filter(|k| !k.is_empty());
+        let model = env::var("OPENAI_MODEL").unwrap_or_else(|_| "gpt-4o-mini".to_string());
+        let embedding_model = env::var("OPENAI_EMBEDDING_MODEL")
+            .unwrap_or_else(|_| "text-embedding-3-small".to_string());
+        // text-embedding-3 models can be shortened to 768 to match cases.embedding;
+        // leave unset for servers that reject the parameter.
+        let embedding_dimensions = env::var("OPENAI_EMBEDDING_DIMENSIONS")
+            .ok()
+            .and_then(|d| d.parse().ok());
+
+        Self {
+            client,
+            base_url,
+            api_key,
+            model,
+            embedding_model,
+            embedding_dimensions,
+        }
+    }
+
+    async fn post<T: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+        let mut req = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("Content-Type", "application/json")
+            .json(body);
+        if let Some(api_key) = &self.api_key {
+            req = req.bearer_auth(api_key);
+        }
+
+        let res = req.send().await?;
+        if res.status().is_success() {
+            Ok(res)
+        } else {
+            let status = res.status();
+            let error_text = res.text().await?;
+            Err(ApiError {
+                provider: "OpenAI-compatible",
+                status,
+                body: error_text,
+            }
+            .into())
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    async fn generate(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let body = ChatCompletionRequest::from_llm(&self.model, request, false);
+        let res = self.post("/chat/completions", &body).await?;
+
+        let parsed: ChatCompletionResponse = res.json().await?;
+        parsed
+            .text()
+            .ok_or_else(|| "Unexpected response structure from chat completions API".into())
+    }
+
+    async fn stream(
+        &self,
+        request: &LlmRequest,
+    ) -> Result<TextStream, Box<dyn std::error::Error + Send + Sync>> {
+        let body = ChatCompletionRequest::from_llm(&self.model, request, true);
+        let res = self.post("/chat/completions", &body).await?;
+
+        // Each event is a `data: {chunk}` line and the stream ends with `data: [DONE]`.
+        let stream = sse_data(res)
+            .take_while(|data| {
+                let done = matches!(data, Ok(d) if d == "[DONE]");
+                async move { !done }
+            })
+            .filter_map(|data| async move {
+                match data {
+                    Ok(data) => match serde_json::from_str::<ChatCompletionResponse>(&data) {
+                        Ok(parsed) => parsed.text().filter(|t| !t.is_empty()).map(Ok),
+                        Err(e) => Some(Err(e.into())),
+                    },
+                    Err(e) => Some(Err(e)),
+                }
+            });
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn embed(
+        &self,
+        text: &str,
+        _task: EmbeddingTask,
+    ) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = EmbeddingRequest {
+            model: &self.embedding_model,
+            input: text,
+            dimensions: self.embedding_dimensions,
+        };
+        let res = self.post("/embeddings", &body).await?;
+
+        let parsed: EmbeddingResponse = res.json().await?;
+        parsed
+            .data
+            .into_iter()
+            .next()
+            .map(|d| d.embedding)
+            .ok_or_else(|| "Embeddings API returned no data".into())
+    }
+}
@@ -1 +1,13 @@
 pub mod ai;
+pub mod citation_graph;
+pub mod citations;
+pub mod embeddings;
+pub mod essays;
+pub mod exams;
+pub mod llm;
+pub mod practice;
+pub mod provisions;
+pub mod question_drafts;
+pub mod retrieval;
+pub mod review;
+pub mod text;
@@ -0,0 +1,363 @@
+use crate::models::exams::AnswerKey;
+use crate::models::practice::{
+    CategoryScore, PracticeSession, SessionDetail, SessionQuestion, StartSessionRequest,
+};
+use crate::services::exams::is_correct;
+use crate::services::review::enqueue_missed_question;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+const DEFAULT_QUESTIONS: i64 = 10;
+const MAX_QUESTIONS: i64 = 100;
+// Answers sent this long after the deadline still count, to allow for the
+// request being in flight when time ran out.
+const GRACE_SECS: i64 = 5;
+
+const SESSION_COLUMNS: &str =
+    "id, status, time_limit_secs, expires_at, score, total, started_at, completed_at";
+
+pub enum PracticeError {
+    NotFound,
+    Invalid(&'static str),
+    Conflict(&'static str),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for PracticeError {
+    fn from(e: sqlx::Error) -> Self {
+        PracticeError::Database(e)
+    }
+}
+
+/// Draws the questions for a new session and records it. Multiple-choice
+/// questions are drawn at random within each requested category, or across
+/// the whole bank when no categories are named.
+pub async fn start_session(
+    db: &PgPool,
+    user_id: Uuid,
+    request: &StartSessionRequest,
+) -> Result<Uuid, PracticeError> {
+    if request.time_limit_minutes.is_some_and(|m| m <= 0) {
+        return Err(PracticeError::Invalid("The time limit must be positive"));
+    }
+    let requested: i64 = if request.per_category.is_empty() {
+        request.count.unwrap_or(DEFAULT_QUESTIONS)
+    } else {
+        // Any total that overflows is over the limit anyway.
+        request
+            .per_category
+            .values()
+            .try_fold(0i64, |sum, &n| sum.checked_add(n))
+            .unwrap_or(i64::MAX)
+    };
+    if request.per_category.values().any(|&n| n <= 0) || requested <= 0 {
+        return Err(PracticeError::Invalid("Ask for at least one question"));
+    }
+    if requested > MAX_QUESTIONS {
+        return Err(PracticeError::Invalid(
+            "A session can have at most 100 questions",
+        ));
+    }
+
+    let mut questions = Vec::new();
+    if request.per_category.is_empty() {
+        questions = draw_questions(db, request, request.category.as_deref(), requested).await?;
+    } else {
+        for (category, &count) in &request.per_category {
+            questions.extend(draw_questions(db, request, Some(category), count).await?);
+        }
+    }
+    // Category names differing only in case draw from the same questions.
+    let mut seen = HashSet::new();
+    questions.retain(|id| seen.insert(*id));
+    if questions.is_empty() {
+        return Err(PracticeError::NotFound);
+    }
+
+    let mut tx = db.begin().await?;
+
+    let time_limit_secs = request.time_limit_minutes.map(|m| m.saturating_mul(60));
+    let session_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO practice_sessions (user_id, time_limit_secs, expires_at, total) \
+         VALUES ($1, $2, NOW() + make_interval(secs => $2), $3) RETURNING id",
+    )
+    .bind(user_id)
+    .bind(time_limit_secs)
+    .bind(questions.len() as i32)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO practice_answers (session_id, question_id, position) \
+         SELECT $1, question_id, position::INTEGER \
+         FROM unnest($2::UUID[]) WITH ORDINALITY AS q(question_id, position)",
+    )
+    .bind(session_id)
+    .bind(&questions)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(session_id)
+}
+
+async fn draw_questions(
+    db: &PgPool,
+    request: &StartSessionRequest,
+    category: Option<&str>,
+    count: i64,
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let mut query = QueryBuilder::<Postgres>::new(
+        "SELECT id FROM exam_questions WHERE question_type = 'multiple_choice'",
+    );
+
+    if let Some(category) = category.filter(|c| !c.is_empty()) {
+        query.push(" AND lower(law_category) = lower(");
+        query.push_bind(category.to_string());
+        query.push(")");
+    }
+    if let Some(difficulty) = request.difficulty.as_deref().filter(|d| !d.is_empty()) {
+        query.push(" AND lower(difficulty) = lower(");
+        query.push_bind(difficulty.to_string());
+        query.push(")");
+    }
+    if let Some(source) = request.source.as_deref().filter(|s| !s.is_empty()) {
+        query.push(" AND lower(source) = lower(");
+        query.push_bind(source.to_string());
+        query.push(")");
+    }
+
+    query.push(" ORDER BY random() LIMIT ");
+    query.push_bind(count);
+
+    query.build_query_scalar::<Uuid>().fetch_all(db).await
+}
+
+// Whether a session's time, grace period included, ran out before `now`.
+// Answers and reads both go by this, so a read never closes a session an
+// answer may still reach.
+fn time_is_up(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    expires_at.is_some_and(|deadline| deadline + Duration::seconds(GRACE_SECS) < now)
+}
+
+/// Saves (or changes) the answer to one question of a session still in
+/// progress. Nothing is marked until the session is submitted.
+pub async fn save_answer(
+    db: &PgPool,
+    user_id: Uuid,
+    session_id: Uuid,
+    question_id: Uuid,
+    answer: &str,
+) -> Result<(), PracticeError> {
+    let (status, expires_at) = sqlx::query_as::<_, (String, Option<DateTime<Utc>>)>(
+        "SELECT status, expires_at FROM practice_sessions WHERE id = $1 AND user_id = $2",
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(PracticeError::NotFound)?;
+
+    if status != "in_progress" {
+        return Err(PracticeError::Conflict("The session has been submitted"));
+    }
+    if time_is_up(expires_at, Utc::now()) {
+        grade_session(db, session_id).await?;
+        return Err(PracticeError::Conflict("Time is up for this session"));
+    }
+
+    let updated = sqlx::query(
+        "UPDATE practice_answers SET answer = $3, answered_at = NOW() \
+         WHERE session_id = $1 AND question_id = $2",
+    )
+    .bind(session_id)
+    .bind(question_id)
+    .bind(answer.trim())
+    .execute(db)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(PracticeError::Invalid(
+            "That question is not in this session",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct GradingRow {
+    question_id: Uuid,
+    answer: Option<String>,
+    #[sqlx(flatten)]
+    key: AnswerKey,
+}
+
+/// Marks every answer in a session and records the score. Unanswered
+/// questions count as wrong, and every wrong one joins the user's review
+/// queue. Grading a session twice does nothing.
+pub async fn grade_session(db: &PgPool, session_id: Uuid) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let (user_id, status) = sqlx::query_as::<_, (Uuid, String)>(
+        "SELECT user_id, status FROM practice_sessions WHERE id = $1 FOR UPDATE",
+    )
+    .bind(session_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if status != "in_progress" {
+        return Ok(());
+    }
+
+    let rows = sqlx::query_as::<_, GradingRow>(
+        "SELECT a.question_id, a.answer, q.question_type, q.options, q.correct_answer, q.explanation \
+         FROM practice_answers a JOIN exam_questions q ON q.id = a.question_id \
+         WHERE a.session_id = $1",
+    )
+    .bind(session_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut score = 0;
+    for row in &rows {
+        let correct = row
+            .answer
+            .as_deref()
+            .is_some_and(|a| is_correct(&row.key, a));
+        if correct {
+            score += 1;
+        } else {
+            enqueue_missed_question(&mut *tx, user_id, row.question_id).await?;
+        }
+
+        sqlx::query(
+            "UPDATE practice_answers SET correct = $3 WHERE session_id = $1 AND question_id = $2",
+        )
+        .bind(session_id)
+        .bind(row.question_id)
+        .bind(correct)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "UPDATE practice_sessions SET status = 'completed', score = $2, completed_at = NOW() \
+         WHERE id = $1",
+    )
+    .bind(session_id)
+    .bind(score)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await
+}
+
+/// A user's session with its questions. A session whose time ran out is
+/// graded here, so it never stays open past its deadline.
+pub async fn load_session(
+    db: &PgPool,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> Result<Option<SessionDetail>, sqlx::Error> {
+    let Some(mut session) = fetch_session(db, user_id, session_id).await? else {
+        return Ok(None);
+    };
+
+    if session.status == "in_progress" && time_is_up(session.expires_at, Utc::now()) {
+        grade_session(db, session_id).await?;
+        session = fetch_session(db, user_id, session_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)?;
+    }
+    let graded = session.status == "completed";
+
+    let mut questions = sqlx::query_as::<_, SessionQuestion>(
+        "SELECT a.position, q.id, q.question, q.question_type, q.options, q.law_category, q.difficulty, q.source, q.year, \
+                q.created_at, a.answer, a.correct, q.correct_answer, q.explanation \
+         FROM practice_answers a JOIN exam_questions q ON q.id = a.question_id \
+         WHERE a.session_id = $1 ORDER BY a.position",
+    )
+    .bind(session_id)
+    .fetch_all(db)
+    .await?;
+    if !graded {
+        for question in &mut questions {
+            question.correct = None;
+            question.correct_answer = None;
+            question.explanation = None;
+        }
+    }
+
+    let categories = if graded {
+        sqlx::query_as::<_, CategoryScore>(
+            "SELECT q.law_category, COUNT(*) AS total, COUNT(a.answer) AS answered, \
+                    COUNT(*) FILTER (WHERE a.correct) AS correct \
+             FROM practice_answers a JOIN exam_questions q ON q.id = a.question_id \
+             WHERE a.session_id = $1 GROUP BY q.law_category ORDER BY q.law_category",
+        )
+        .bind(session_id)
+        .fetch_all(db)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(Some(SessionDetail {
+        session,
+        questions,
+        categories,
+    }))
+}
+
+async fn fetch_session(
+    db: &PgPool,
+    user_id: Uuid,
+    session_id: Uuid,
+) -> Result<Option<PracticeSession>, sqlx::Error> {
+    sqlx::query_as::<_, PracticeSession>(&format!(
+        "SELECT {} FROM practice_sessions WHERE id = $1 AND user_id = $2",
+        SESSION_COLUMNS
+    ))
+    .bind(session_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// A user's past and current sessions, newest first.
+pub async fn list_sessions(
+    db: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<PracticeSession>, sqlx::Error> {
+    sqlx::query_as::<_, PracticeSession>(&format!(
+        "SELECT {} FROM practice_sessions WHERE user_id = $1 ORDER BY started_at DESC LIMIT $2",
+        SESSION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_runs_out_after_the_grace_period() {
+        let deadline = Utc::now();
+        let at = |secs| deadline + Duration::seconds(secs);
+
+        assert!(!time_is_up(Some(deadline), at(-60)));
+        assert!(!time_is_up(Some(deadline), at(1)));
+        assert!(!time_is_up(Some(deadline), at(GRACE_SECS)));
+        assert!(time_is_up(Some(deadline), at(GRACE_SECS + 1)));
+    }
+
+    #[test]
+    fn untimed_sessions_never_run_out() {
+        assert!(!time_is_up(None, Utc::now()));
+    }
+}
@@ -0,0 +1,851 @@
+use crate::models::statutes::{
+    AmendProvisionRequest, ProvisionNode, ProvisionVersion, StatuteProvision,
+};
+use chrono::NaiveDate;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+// Rows per INSERT when storing a parsed statute.
+const INSERT_BATCH: usize = 500;
+
+/// Levels of a Nigerian enactment. Parts and chapters nest either way round
+/// (the Constitution has parts inside chapters), so their order is decided
+/// per statute by `Parser::level`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvisionKind {
+    Part,
+    Chapter,
+    Section,
+    Subsection,
+    Paragraph,
+}
+
+impl ProvisionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProvisionKind::Part => "part",
+            ProvisionKind::Chapter => "chapter",
+            ProvisionKind::Section => "section",
+            ProvisionKind::Subsection => "subsection",
+            ProvisionKind::Paragraph => "paragraph",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParsedProvision {
+    pub kind: ProvisionKind,
+    pub number: String,
+    pub heading: Option<String>,
+    pub text: String,
+    pub children: Vec<ParsedProvision>,
+}
+
+enum Line<'a> {
+    Marker(ProvisionKind, String, &'a str),
+    Plain(&'a str),
+}
+
+/// Splits statute text into its provisions. The parser is line based and
+/// recognises the usual layout of Nigerian enactments:
+///
+/// - `PART II - ...` and `CHAPTER IV: Fundamental Rights` open parts and chapters
+/// - `36.` or `Section 36` opens a section, optionally followed on the same
+///   line by its first subsection, e.g. `36. (1) In the determination ...`
+/// - `(6)` opens a subsection and `(c)` a paragraph
+///
+/// A short line just before a section is taken as its marginal note
+/// (heading). Roman numerals that do not continue the paragraph lettering,
+/// such as `(ii)` after `(c)`, are kept as text of the enclosing paragraph.
+/// Text before the first provision (a preamble) is not included.
+pub fn parse_provisions(text: &str) -> Vec<ParsedProvision> {
+    let mut parser = Parser::default();
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        parser.line(line);
+    }
+    parser.finish()
+}
+
+#[derive(Default)]
+struct Parser {
+    roots: Vec<ParsedProvision>,
+    open: Vec<ParsedProvision>,
+    // A short plain line that may turn out to be the next section's heading.
+    held: Option<String>,
+    awaiting_heading: bool,
+    // Whichever of part and chapter appears first is the outer division.
+    outer_division: Option<ProvisionKind>,
+}
+
+impl Parser {
+    fn level(&self, kind: ProvisionKind) -> u8 {
+        match kind {
+            ProvisionKind::Part | ProvisionKind::Chapter => {
+                if self.outer_division.is_none_or(|outer| outer == kind) {
+                    0
+                } else {
+                    1
+                }
+            }
+            ProvisionKind::Section => 2,
+            ProvisionKind::Subsection => 3,
+            ProvisionKind::Paragraph => 4,
+        }
+    }
+
+    fn line(&mut self, line: &str) {
+        match classify(line, self.current_paragraph()) {
+            Line::Marker(kind, number, rest) => {
+                let held = self.held.take();
+                let heading = match kind {
+                    ProvisionKind::Section => held,
+                    _ => {
+                        self.append_text(held.as_deref());
+                        None
+                    }
+                };
+                self.open_provision(kind, number, heading, rest);
+            }
+            Line::Plain(text) => {
+                if self.awaiting_heading && looks_like_heading(text) {
+                    if let Some(open) = self.open.last_mut() {
+                        open.heading = Some(text.to_string());
+                    }
+                    self.awaiting_heading = false;
+                    return;
+                }
+                self.awaiting_heading = false;
+
+                let held = self.held.take();
+                self.append_text(held.as_deref());
+                if looks_like_heading(text) {
+                    self.held = Some(text.to_string());
+                } else {
+                    self.append_text(Some(text));
+                }
+            }
+        }
+    }
+
+    fn open_provision(
+        &mut self,
+        kind: ProvisionKind,
+        number: String,
+        mut heading: Option<String>,
+        rest: &str,
+    ) {
+        let is_division = matches!(kind, ProvisionKind::Part | ProvisionKind::Chapter);
+        if is_division && self.outer_division.is_none() {
+            self.outer_division = Some(kind);
+        }
+        self.close_to(kind);
+
+        let mut rest = rest.trim();
+        if is_division {
+            let title = rest
+                .trim_start_matches([':', '-', '–', '—', '.', ' '])
+                .trim();
+            if !title.is_empty() {
+                heading = Some(title.to_string());
+            }
+            rest = "";
+        } else if kind == ProvisionKind::Section && heading.is_none() {
+            // "12. Short title.—(1) This Act may be cited as ..."
+            if let Some((note, body)) = split_marginal_note(rest) {
+                heading = Some(note.to_string());
+                rest = body;
+            }
+        }
+
+        self.awaiting_heading = is_division && heading.is_none();
+        self.open.push(ParsedProvision {
+            kind,
+            number,
+            heading,
+            text: String::new(),
+            children: Vec::new(),
+        });
+
+        if !rest.is_empty() {
+            // The first subsection or paragraph often shares the line.
+            match classify(rest, self.current_paragraph()) {
+                Line::Marker(child, number, child_rest) if self.level(child) > self.level(kind) => {
+                    self.open_provision(child, number, None, child_rest);
+                }
+                _ => self.append_text(Some(rest)),
+            }
+        }
+    }
+
+    fn append_text(&mut self, text: Option<&str>) {
+        let (Some(text), Some(open)) = (text, self.open.last_mut()) else {
+            return;
+        };
+        if !open.text.is_empty() {
+            open.text.push(' ');
+        }
+        open.text.push_str(text);
+    }
+
+    fn current_paragraph(&self) -> Option<&str> {
+        self.open
+            .last()
+            .filter(|p| p.kind == ProvisionKind::Paragraph)
+            .map(|p| p.number.as_str())
+    }
+
+    // Closes every open provision at `kind`'s level or below.
+    fn close_to(&mut self, kind: ProvisionKind) {
+        let level = self.level(kind);
+        while self
+            .open
+            .last()
+            .is_some_and(|p| self.level(p.kind) >= level)
+        {
+            self.close_last();
+        }
+    }
+
+    fn close_last(&mut self) {
+        if let Some(done) = self.open.pop() {
+            match self.open.last_mut() {
+                Some(parent) => parent.children.push(done),
+                None => self.roots.push(done),
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<ParsedProvision> {
+        let held = self.held.take();
+        self.append_text(held.as_deref());
+        while !self.open.is_empty() {
+            self.close_last();
+        }
+        self.roots
+    }
+}
+
+fn classify<'a>(line: &'a str, current_paragraph: Option<&str>) -> Line<'a> {
+    for (prefix, kind) in [
+        ("part ", ProvisionKind::Part),
+        ("chapter ", ProvisionKind::Chapter),
+    ] {
+        if let Some(after) = strip_prefix_ignore_case(line, prefix) {
+            let token_end = after
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(after.len());
+            let token = &after[..token_end];
+            if !token.is_empty() && (is_roman(token) || token.chars().all(|c| c.is_ascii_digit())) {
+                return Line::Marker(kind, token.to_uppercase(), &after[token_end..]);
+            }
+        }
+    }
+
+    if let Some((number, rest)) = section_number(line) {
+        return Line::Marker(ProvisionKind::Section, number, rest);
+    }
+
+    if let Some((marker, rest)) = bracketed(line) {
+        if marker.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            return Line::Marker(ProvisionKind::Subsection, marker.to_lowercase(), rest);
+        }
+        if marker.chars().all(|c| c.is_ascii_lowercase()) && marker.len() <= 4 {
+            let continues_lettering = match current_paragraph {
+                Some(previous) => next_letter(previous).as_deref() == Some(marker),
+                None => true,
+            };
+            if continues_lettering || !is_roman(marker) {
+                return Line::Marker(ProvisionKind::Paragraph, marker.to_string(), rest);
+            }
+        }
+    }
+
+    Line::Plain(line)
+}
+
+// "36.", "36A.", "Section 36" or "S. 36" at the start of a line.
+fn section_number(line: &str) -> Option<(String, &str)> {
+    let (after, explicit) = match strip_prefix_ignore_case(line, "section ")
+        .or_else(|| strip_prefix_ignore_case(line, "s. "))
+    {
+        Some(after) => (after, true),
+        None => (line, false),
+    };
+
+    let digits = after.chars().take_while(|c| c.is_ascii_digit()).count();
+    // Three digits at most, so a year opening a line is not read as a section.
+    if digits == 0 || digits > 3 {
+        return None;
+    }
+    let letters = after[digits..]
+        .chars()
+        .take_while(|c| c.is_ascii_uppercase())
+        .count();
+    let number_end = digits + letters;
+    let number = &after[..number_end];
+    let rest = &after[number_end..];
+
+    if let Some(body) = rest.strip_prefix('.') {
+        if body.is_empty() || body.starts_with(' ') {
+            return Some((number.to_lowercase(), body));
+        }
+    } else if explicit && (rest.is_empty() || rest.starts_with([' ', ':', '-'])) {
+        return Some((number.to_lowercase(), rest));
+    }
+    None
+}
+
+fn strip_prefix_ignore_case<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.get(..prefix.len())
+        .filter(|head| head.eq_ignore_ascii_case(prefix))
+        .map(|_| &line[prefix.len()..])
+}
+
+// "(6) rest" or "(c) rest".
+fn bracketed(line: &str) -> Option<(&str, &str)> {
+    let inner = line.strip_prefix('(')?;
+    let close = inner.find(')')?;
+    let marker = &inner[..close];
+    let rest = &inner[close + 1..];
+    if marker.is_empty()
+        || marker.len() > 4
+        || !marker.chars().all(|c| c.is_ascii_alphanumeric())
+        || !(rest.is_empty() || rest.starts_with(' '))
+    {
+        return None;
+    }
+    Some((marker, rest))
+}
+
+fn split_marginal_note(rest: &str) -> Option<(&str, &str)> {
+    for separator in [".—", ".–", ".-"] {
+        if let Some(at) = rest.find(separator)
+            && at > 0
+            && at <= 100
+        {
+            return Some((rest[..at].trim(), rest[at + separator.len()..].trim()));
+        }
+    }
+    None
+}
+
+fn looks_like_heading(line: &str) -> bool {
+    line.chars().count() <= 80 && !line.ends_with(['.', ';', ':', ',']) && !line.starts_with('(')
+}
+
+fn is_roman(token: &str) -> bool {
+    !token.is_empty()
+        && token.chars().all(|c| {
+            matches!(
+                c.to_ascii_lowercase(),
+                'i' | 'v' | 'x' | 'l' | 'c' | 'd' | 'm'
+            )
+        })
+}
+
+// The paragraph letter after `letter`: "c" → "d", "z" → "aa".
+fn next_letter(letter: &str) -> Option<String> {
+    let mut chars = letter.chars();
+    let first = chars.next()?;
+    if !first.is_ascii_lowercase() || !chars.all(|c| c == first) {
+        return None;
+    }
+    Some(if first == 'z' {
+        "a".repeat(letter.len() + 1)
+    } else {
+        ((first as u8 + 1) as char).to_string().repeat(letter.len())
+    })
+}
+
+struct NewProvision<'a> {
+    id: Uuid,
+    parent_id: Option<Uuid>,
+    provision: &'a ParsedProvision,
+    path: Option<String>,
+    position: i32,
+}
+
+/// Replaces the stored provisions of a statute with `provisions`, in one
+/// transaction. Provisions that keep their path (or, for parts and chapters,
+/// their number) keep their id, so amendment history recorded against them
+/// survives a re-parse. Returns the number of provisions stored.
+pub async fn replace_provisions(
+    db: &PgPool,
+    statute_id: Uuid,
+    provisions: &[ParsedProvision],
+) -> Result<usize, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let existing = sqlx::query_as::<_, (Uuid, String, String, Option<String>)>(
+        "SELECT id, kind, number, path FROM statute_provisions WHERE statute_id = $1",
+    )
+    .bind(statute_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let mut existing_ids: HashMap<String, Uuid> = existing
+        .into_iter()
+        .map(|(id, kind, number, path)| (match_key(&kind, &number, path.as_deref()), id))
+        .collect();
+
+    let mut rows = Vec::new();
+    let mut seen_paths = HashSet::new();
+    flatten(
+        provisions,
+        None,
+        None,
+        &mut rows,
+        &mut seen_paths,
+        &mut existing_ids,
+    );
+
+    // Parents come before their children in `rows`, so foreign keys hold
+    // within and across batches.
+    for batch in rows.chunks(INSERT_BATCH) {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "INSERT INTO statute_provisions (id, statute_id, parent_id, kind, number, heading, text, path, position) ",
+        );
+        query.push_values(batch, |mut row, p| {
+            row.push_bind(p.id)
+                .push_bind(statute_id)
+                .push_bind(p.parent_id)
+                .push_bind(p.provision.kind.as_str())
+                .push_bind(&p.provision.number)
+                .push_bind(&p.provision.heading)
+                .push_bind(&p.provision.text)
+                .push_bind(&p.path)
+                .push_bind(p.position);
+        });
+        query.push(
+            " ON CONFLICT (id) DO UPDATE SET parent_id = EXCLUDED.parent_id, kind = EXCLUDED.kind, \
+             number = EXCLUDED.number, heading = EXCLUDED.heading, text = EXCLUDED.text, \
+             path = EXCLUDED.path, position = EXCLUDED.position",
+        );
+        query.build().execute(&mut *tx).await?;
+    }
+
+    let kept: Vec<Uuid> = rows.iter().map(|r| r.id).collect();
+    sqlx::query("DELETE FROM statute_provisions WHERE statute_id = $1 AND NOT (id = ANY($2))")
+        .bind(statute_id)
+        .bind(&kept)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(rows.len())
+}
+
+// How a stored provision is matched to a freshly parsed one.
+fn match_key(kind: &str, number: &str, path: Option<&str>) -> String {
+    match path {
+        Some(path) => path.to_string(),
+        None => format!("{}:{}", kind, number),
+    }
+}
+
+fn flatten<'a>(
+    provisions: &'a [ParsedProvision],
+    parent_id: Option<Uuid>,
+    parent_path: Option<&str>,
+    rows: &mut Vec<NewProvision<'a>>,
+    seen_paths: &mut HashSet<String>,
+    existing_ids: &mut HashMap<String, Uuid>,
+) {
+    for provision in provisions {
+        let path = match (provision.kind, parent_path) {
+            (ProvisionKind::Part | ProvisionKind::Chapter, _) => None,
+            (ProvisionKind::Section, _) => Some(provision.number.to_lowercase()),
+            (_, Some(parent)) => Some(format!("{}/{}", parent, provision.number.to_lowercase())),
+            (_, None) => None,
+        };
+        // Schedules sometimes restart numbering; only the first use of a path
+        // is addressable.
+        let path = path.filter(|p| seen_paths.insert(p.clone()));
+
+        let id = existing_ids
+            .remove(&match_key(
+                provision.kind.as_str(),
+                &provision.number,
+                path.as_deref(),
+            ))
+            .unwrap_or_else(Uuid::new_v4);
+        let position = rows.len() as i32;
+        rows.push(NewProvision {
+            id,
+            parent_id,
+            provision,
+            path: path.clone(),
+            position,
+        });
+
+        let child_path = match provision.kind {
+            ProvisionKind::Part | ProvisionKind::Chapter => parent_path.map(str::to_string),
+            _ => path,
+        };
+        flatten(
+            &provision.children,
+            Some(id),
+            child_path.as_deref(),
+            rows,
+            seen_paths,
+            existing_ids,
+        );
+    }
+}
+
+/// Nests provisions (in document order) under their parents. Rows whose
+/// parent is not in `rows` become roots.
+pub fn provision_tree(rows: Vec<StatuteProvision>) -> Vec<ProvisionNode> {
+    let ids: HashSet<Uuid> = rows.iter().map(|r| r.id).collect();
+    let mut children: HashMap<Option<Uuid>, Vec<StatuteProvision>> = HashMap::new();
+    for row in rows {
+        let parent = row.parent_id.filter(|p| ids.contains(p));
+        children.entry(parent).or_default().push(row);
+    }
+    build(None, &mut children)
+}
+
+fn build(
+    parent: Option<Uuid>,
+    children: &mut HashMap<Option<Uuid>, Vec<StatuteProvision>>,
+) -> Vec<ProvisionNode> {
+    children
+        .remove(&parent)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|row| {
+            let nested = build(Some(row.id), children);
+            ProvisionNode::new(row, nested)
+        })
+        .collect()
+}
+
+pub enum AmendError {
+    NotFound,
+    Conflict(&'static str),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for AmendError {
+    fn from(e: sqlx::Error) -> Self {
+        AmendError::Database(e)
+    }
+}
+
+/// Records an amendment to the provision at `path`. The text in force until
+/// now is closed off at `effective_from` (and saved as the original version
+/// if this is the first amendment), then either the new text starts or, for
+/// a repeal, the provision and everything under it stop being in force.
+/// Returns the new version, or `None` for a repeal.
+pub async fn amend_provision(
+    db: &PgPool,
+    statute_id: Uuid,
+    path: &str,
+    amendment: &AmendProvisionRequest,
+) -> Result<Option<ProvisionVersion>, AmendError> {
+    let mut tx = db.begin().await?;
+
+    let (provision_id, heading, text, in_force_from, repealed_from) = sqlx::query_as::<
+        _,
+        (
+            Uuid,
+            Option<String>,
+            String,
+            Option<NaiveDate>,
+            Option<NaiveDate>,
+        ),
+    >(
+        "SELECT id, heading, text, effective_from, effective_to FROM statute_provisions \
+             WHERE statute_id = $1 AND path = $2 FOR UPDATE",
+    )
+    .bind(statute_id)
+    .bind(path)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AmendError::NotFound)?;
+
+    if repealed_from.is_some() {
+        return Err(AmendError::Conflict("Provision has already been repealed"));
+    }
+
+    let current = sqlx::query_as::<_, (Uuid, Option<NaiveDate>)>(
+        "SELECT id, effective_from FROM statute_provision_versions \
+         WHERE provision_id = $1 AND effective_to IS NULL",
+    )
+    .bind(provision_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let current_from = match current {
+        Some((_, from)) => from,
+        None => in_force_from,
+    };
+    if current_from.is_some_and(|from| from >= amendment.effective_from) {
+        return Err(AmendError::Conflict(
+            "Amendments must take effect after the text currently in force",
+        ));
+    }
+
+    match current {
+        Some((version_id, _)) => {
+            sqlx::query("UPDATE statute_provision_versions SET effective_to = $2 WHERE id = $1")
+                .bind(version_id)
+                .bind(amendment.effective_from)
+                .execute(&mut *tx)
+                .await?;
+        }
+        None => {
+            // First amendment: keep the enacted text as a version of its own.
+            sqlx::query(
+                "INSERT INTO statute_provision_versions (provision_id, heading, text, effective_from, effective_to) \
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(provision_id)
+            .bind(&heading)
+            .bind(&text)
+            .bind(in_force_from)
+            .bind(amendment.effective_from)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    if amendment.repeal {
+        sqlx::query(
+            "WITH RECURSIVE subtree AS ( \
+                 SELECT id FROM statute_provisions WHERE id = $1 \
+                 UNION ALL \
+                 SELECT c.id FROM statute_provisions c JOIN subtree s ON c.parent_id = s.id \
+             ) \
+             UPDATE statute_provisions SET effective_to = $2, repealed_by = $3 \
+             WHERE id IN (SELECT id FROM subtree) AND effective_to IS NULL",
+        )
+        .bind(provision_id)
+        .bind(amendment.effective_from)
+        .bind(&amendment.amended_by)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        return Ok(None);
+    }
+
+    let new_text = amendment.text.as_deref().unwrap_or_default();
+    let new_heading = amendment.heading.clone().or(heading);
+
+    let version = sqlx::query_as::<_, ProvisionVersion>(
+        "INSERT INTO statute_provision_versions (provision_id, heading, text, effective_from, amended_by) \
+         VALUES ($1, $2, $3, $4, $5) \
+         RETURNING id, heading, text, effective_from, effective_to, amended_by",
+    )
+    .bind(provision_id)
+    .bind(&new_heading)
+    .bind(new_text)
+    .bind(amendment.effective_from)
+    .bind(&amendment.amended_by)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // statute_provisions keeps the latest text for search and re-parsing.
+    sqlx::query("UPDATE statute_provisions SET heading = $2, text = $3 WHERE id = $1")
+        .bind(provision_id)
+        .bind(&new_heading)
+        .bind(new_text)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "kind number" for a provision and its descendants, in document order.
+    fn outline(provisions: &[ParsedProvision]) -> Vec<String> {
+        let mut lines = Vec::new();
+        for p in provisions {
+            lines.push(format!("{} {}", p.kind.as_str(), p.number));
+            lines.extend(outline(&p.children));
+        }
+        lines
+    }
+
+    #[test]
+    fn nests_subsections_and_paragraphs() {
+        let parsed = parse_provisions(
+            "CHAPTER IV: Fundamental Rights\n\
+             36. (1) In the determination of his civil rights a person shall be entitled to a fair hearing.\n\
+             (6) Every person charged with a criminal offence shall be entitled to -\n\
+             (a) be informed promptly;\n\
+             (b) be given adequate time; and\n\
+             (c) defend himself in person.\n\
+             37. The privacy of citizens is guaranteed.",
+        );
+
+        assert_eq!(
+            outline(&parsed),
+            [
+                "chapter IV",
+                "section 36",
+                "subsection 1",
+                "subsection 6",
+                "paragraph a",
+                "paragraph b",
+                "paragraph c",
+                "section 37",
+            ]
+        );
+        assert_eq!(parsed[0].heading.as_deref(), Some("Fundamental Rights"));
+        let six = &parsed[0].children[0].children[1];
+        assert_eq!(
+            six.text,
+            "Every person charged with a criminal offence shall be entitled to -"
+        );
+        assert_eq!(six.children[2].text, "defend himself in person.");
+    }
+
+    #[test]
+    fn keeps_roman_numerals_inside_the_paragraph() {
+        let parsed = parse_provisions(
+            "5. (1) The powers include -\n\
+             (a) the power to -\n\
+             (i) make laws;\n\
+             (ii) raise taxes;\n\
+             (b) any other power.",
+        );
+
+        assert_eq!(
+            outline(&parsed),
+            ["section 5", "subsection 1", "paragraph a", "paragraph b"]
+        );
+        assert_eq!(
+            parsed[0].children[0].children[0].text,
+            "the power to - (i) make laws; (ii) raise taxes;"
+        );
+    }
+
+    #[test]
+    fn continues_paragraph_lettering_past_z() {
+        let mut text = String::from("2. In this Act -\n");
+        for letter in 'a'..='z' {
+            text.push_str(&format!("({}) term {};\n", letter, letter));
+        }
+        text.push_str("(aa) term aa;\n(bb) term bb;\n(ii) not a new paragraph;\n(cc) term cc.");
+        let parsed = parse_provisions(&text);
+
+        let numbers: Vec<&str> = parsed[0]
+            .children
+            .iter()
+            .map(|p| p.number.as_str())
+            .collect();
+        assert_eq!(numbers.len(), 29);
+        assert_eq!(&numbers[24..], ["y", "z", "aa", "bb", "cc"]);
+        assert_eq!(
+            parsed[0].children[27].text,
+            "term bb; (ii) not a new paragraph;"
+        );
+    }
+
+    #[test]
+    fn next_letter_doubles_after_z() {
+        assert_eq!(next_letter("a").as_deref(), Some("b"));
+        assert_eq!(next_letter("z").as_deref(), Some("aa"));
+        assert_eq!(next_letter("aa").as_deref(), Some("bb"));
+        assert_eq!(next_letter("zz").as_deref(), Some("aaa"));
+        assert_eq!(next_letter("ab"), None);
+        assert_eq!(next_letter("A"), None);
+    }
+
+    #[test]
+    fn reads_headings_without_section_numbers() {
+        let parsed = parse_provisions(
+            "An Act to make provision for the matters below.\n\
+             PART I\n\
+             Preliminary\n\
+             1. Short title.—This Act may be cited as the Example Act.\n\
+             Interpretation\n\
+             2. In this Act, unless the context otherwise requires, words have their usual meaning.\n\
+             Transitional provisions\n\
+             The Minister may make regulations.",
+        );
+
+        assert_eq!(outline(&parsed), ["part I", "section 1", "section 2"]);
+        assert_eq!(parsed[0].heading.as_deref(), Some("Preliminary"));
+        let sections = &parsed[0].children;
+        assert_eq!(sections[0].heading.as_deref(), Some("Short title"));
+        assert_eq!(
+            sections[0].text,
+            "This Act may be cited as the Example Act."
+        );
+        assert_eq!(sections[1].heading.as_deref(), Some("Interpretation"));
+        // A heading that no section follows stays in the text, unnumbered.
+        assert!(
+            sections[1]
+                .text
+                .ends_with("Transitional provisions The Minister may make regulations.")
+        );
+    }
+
+    #[test]
+    fn classifies_section_markers() {
+        assert_eq!(
+            section_number("36. (1) In the"),
+            Some(("36".to_string(), " (1) In the"))
+        );
+        assert_eq!(
+            section_number("Section 12A: Interpretation").map(|(n, _)| n),
+            Some("12a".to_string())
+        );
+        assert_eq!(
+            section_number("S. 4").map(|(n, _)| n),
+            Some("4".to_string())
+        );
+        // Years and decimals are not sections.
+        assert_eq!(section_number("1999. The year"), None);
+        assert_eq!(section_number("3.5 per cent"), None);
+        assert_eq!(section_number("12 months"), None);
+    }
+
+    #[test]
+    fn flatten_builds_paths_and_keeps_existing_ids() {
+        let parsed = parse_provisions(
+            "PART I\n\
+             1. (1) First.\n\
+             (a) one;\n\
+             SCHEDULE\n\
+             PART II\n\
+             1. Restarted numbering.",
+        );
+        let kept = Uuid::new_v4();
+        let mut existing = HashMap::from([("1/1/a".to_string(), kept)]);
+        let mut rows = Vec::new();
+        flatten(
+            &parsed,
+            None,
+            None,
+            &mut rows,
+            &mut HashSet::new(),
+            &mut existing,
+        );
+
+        let paths: Vec<Option<&str>> = rows.iter().map(|r| r.path.as_deref()).collect();
+        assert_eq!(
+            paths,
+            [None, Some("1"), Some("1/1"), Some("1/1/a"), None, None]
+        );
+        assert_eq!(rows[3].id, kept);
+        assert_eq!(rows[2].parent_id, Some(rows[1].id));
+        assert_eq!(rows[1].parent_id, Some(rows[0].id));
+        let positions: Vec<i32> = rows.iter().map(|r| r.position).collect();
+        assert_eq!(positions, [0, 1, 2, 3, 4, 5]);
+        assert!(existing.is_empty());
+    }
+
+    #[test]
+    fn ignores_empty_text() {
+        assert!(parse_provisions("").is_empty());
+        assert!(parse_provisions("\n  \n").is_empty());
+    }
+}
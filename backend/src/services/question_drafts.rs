@@ -0,0 +1,365 @@
+use crate::models::exams::{
+    ApproveDraftRequest, GenerateQuestionsRequest, QuestionDraft, QuestionType,
+};
+use crate::services::ai::AiService;
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const DEFAULT_DRAFTS: usize = 5;
+const MAX_DRAFTS: usize = 10;
+const MIN_OPTIONS: usize = 2;
+const MAX_OPTIONS: usize = 6;
+/// `exam_questions.source` of approved AI drafts, so students can tell them
+/// apart from past papers.
+const GENERATED_SOURCE: &str = "AI Generated";
+
+const DRAFT_COLUMNS: &str = "id, status, question_type, question, options, correct_answer, \
+     explanation, law_category, difficulty, source_case_id, source_topic_id, reviewed_by, \
+     review_note, reviewed_at, published_question_id, created_at";
+
+pub enum DraftError {
+    NotFound,
+    Invalid(String),
+    Conflict(&'static str),
+    Ai(String),
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for DraftError {
+    fn from(e: sqlx::Error) -> Self {
+        DraftError::Database(e)
+    }
+}
+
+// What the questions are drafted from.
+struct Material {
+    text: String,
+    law_category: Option<String>,
+    case_id: Option<Uuid>,
+    topic_id: Option<Uuid>,
+}
+
+/// Drafts questions from a case or topic and queues the usable ones for
+/// moderation. Drafts that fail validation (e.g. a correct answer that is
+/// not one of the options) are dropped.
+pub async fn generate_drafts(
+    db: &PgPool,
+    ai: &AiService,
+    request: &GenerateQuestionsRequest,
+) -> Result<Vec<QuestionDraft>, DraftError> {
+    let count = request.count.unwrap_or(DEFAULT_DRAFTS);
+    if !(1..=MAX_DRAFTS).contains(&count) {
+        return Err(DraftError::Invalid(format!(
+            "Ask for between 1 and {} questions",
+            MAX_DRAFTS
+        )));
+    }
+
+    let material = load_material(db, request).await?;
+    let law_category = request
+        .law_category
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .or(material.law_category)
+        .ok_or_else(|| DraftError::Invalid("Give a law_category for this material".to_string()))?;
+
+    let generated = ai
+        .generate_questions(&material.text, request.question_type, count)
+        .await
+        .map_err(|e| DraftError::Ai(e.to_string()))?;
+
+    let mut drafts = Vec::new();
+    for question in generated {
+        let Ok(correct_answer) = validate(
+            request.question_type,
+            &question.question,
+            &question.options,
+            &question.correct_answer,
+        ) else {
+            continue;
+        };
+        let options = (request.question_type == QuestionType::MultipleChoice)
+            .then(|| Value::from(question.options));
+
+        let draft = sqlx::query_as::<_, QuestionDraft>(&format!(
+            "INSERT INTO generated_questions (question_type, question, options, correct_answer, \
+                 explanation, law_category, difficulty, source_case_id, source_topic_id) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING {}",
+            DRAFT_COLUMNS
+        ))
+        .bind(request.question_type.as_str())
+        .bind(question.question.trim())
+        .bind(options)
+        .bind(correct_answer)
+        .bind(question.explanation.trim())
+        .bind(&law_category)
+        .bind(question.difficulty)
+        .bind(material.case_id)
+        .bind(material.topic_id)
+        .fetch_one(db)
+        .await?;
+        drafts.push(draft);
+    }
+
+    Ok(drafts)
+}
+
+async fn load_material(
+    db: &PgPool,
+    request: &GenerateQuestionsRequest,
+) -> Result<Material, DraftError> {
+    match (request.case_id, request.topic_id) {
+        (Some(case_id), None) => {
+            // Many cases carry only a summary; draft from that when there is
+            // no judgment text.
+            let (title, citation, law_category, content) =
+                sqlx::query_as::<_, (String, String, Option<String>, Option<String>)>(
+                    "SELECT title, citation, coalesce(law_category, topic), \
+                         coalesce(nullif(btrim(content), ''), summary) \
+                     FROM cases WHERE id = $1",
+                )
+                .bind(case_id)
+                .fetch_optional(db)
+                .await?
+                .ok_or(DraftError::NotFound)?;
+            let content = content.filter(|c| !c.trim().is_empty()).ok_or_else(|| {
+                DraftError::Invalid("The case has neither content nor a summary".to_string())
+            })?;
+
+            Ok(Material {
+                text: format!("{} {}\n\n{}", title, citation, content),
+                law_category,
+                case_id: Some(case_id),
+                topic_id: None,
+            })
+        }
+        (None, Some(topic_id)) => {
+            let (course, title, content) =
+                sqlx::query_as::<_, (Option<String>, String, Option<String>)>(
+                    "SELECT c.title, t.title, t.content \
+                     FROM topics t LEFT JOIN courses c ON c.id = t.course_id WHERE t.id = $1",
+                )
+                .bind(topic_id)
+                .fetch_optional(db)
+                .await?
+                .ok_or(DraftError::NotFound)?;
+            let content = content
+                .filter(|c| !c.trim().is_empty())
+                .ok_or_else(|| DraftError::Invalid("The topic has no content".to_string()))?;
+
+            Ok(Material {
+                text: format!("{}\n\n{}", title, content),
+                law_category: course,
+                case_id: None,
+                topic_id: Some(topic_id),
+            })
+        }
+        _ => Err(DraftError::Invalid(
+            "Give exactly one of case_id and topic_id".to_string(),
+        )),
+    }
+}
+
+/// Checks a question is publishable and returns its correct answer, spelled
+/// as the matching option for multiple choice.
+fn validate(
+    question_type: QuestionType,
+    question: &str,
+    options: &[String],
+    correct_answer: &str,
+) -> Result<String, String> {
+    if question.trim().is_empty() {
+        return Err("The question is empty".to_string());
+    }
+    let correct_answer = correct_answer.trim();
+    if correct_answer.is_empty() {
+        return Err("The correct answer is empty".to_string());
+    }
+
+    match question_type {
+        QuestionType::Essay => {
+            if !options.is_empty() {
+                return Err("Essay questions have no options".to_string());
+            }
+            Ok(correct_answer.to_string())
+        }
+        QuestionType::MultipleChoice => {
+            if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()) {
+                return Err(format!(
+                    "Multiple-choice questions need {} to {} options",
+                    MIN_OPTIONS, MAX_OPTIONS
+                ));
+            }
+            if options.iter().any(|o| o.trim().is_empty()) {
+                return Err("An option is empty".to_string());
+            }
+            for (i, option) in options.iter().enumerate() {
+                if options[..i]
+                    .iter()
+                    .any(|o| o.trim().eq_ignore_ascii_case(option.trim()))
+                {
+                    return Err("Two options are the same".to_string());
+                }
+            }
+            options
+                .iter()
+                .map(|o| o.trim())
+                .find(|o| o.eq_ignore_ascii_case(correct_answer))
+                .map(str::to_string)
+                .ok_or_else(|| "The correct answer is not one of the options".to_string())
+        }
+    }
+}
+
+/// Publishes a pending draft to `exam_questions`, applying the moderator's
+/// corrections first.
+pub async fn approve_draft(
+    db: &PgPool,
+    id: Uuid,
+    reviewer: Uuid,
+    edits: &ApproveDraftRequest,
+) -> Result<QuestionDraft, DraftError> {
+    let mut tx = db.begin().await?;
+
+    let draft = sqlx::query_as::<_, QuestionDraft>(&format!(
+        "SELECT {} FROM generated_questions WHERE id = $1 FOR UPDATE",
+        DRAFT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(DraftError::NotFound)?;
+    if draft.status != "pending" {
+        return Err(DraftError::Conflict(
+            "The question has already been moderated",
+        ));
+    }
+
+    let question_type = if draft.question_type == "essay" {
+        QuestionType::Essay
+    } else {
+        QuestionType::MultipleChoice
+    };
+    let question = edits.question.clone().unwrap_or(draft.question);
+    let options = edits.options.clone().unwrap_or_else(|| {
+        draft
+            .options
+            .as_ref()
+            .and_then(Value::as_array)
+            .map(|o| {
+                o.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    });
+    let correct_answer = validate(
+        question_type,
+        &question,
+        &options,
+        edits
+            .correct_answer
+            .as_deref()
+            .unwrap_or(&draft.correct_answer),
+    )
+    .map_err(DraftError::Invalid)?;
+    let explanation = edits.explanation.clone().or(draft.explanation);
+    let law_category = edits.law_category.clone().unwrap_or(draft.law_category);
+    let difficulty = edits.difficulty.clone().or(draft.difficulty);
+    let options = (question_type == QuestionType::MultipleChoice).then(|| Value::from(options));
+
+    let published_id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO exam_questions (question, question_type, options, correct_answer, explanation, \
+             law_category, difficulty, source) \
+         VALUES ($1, $2, $3, $4, $5, $6, coalesce($7, 'Medium'), $8) RETURNING id",
+    )
+    .bind(question.trim())
+    .bind(question_type.as_str())
+    .bind(&options)
+    .bind(&correct_answer)
+    .bind(&explanation)
+    .bind(&law_category)
+    .bind(&difficulty)
+    .bind(GENERATED_SOURCE)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let approved = sqlx::query_as::<_, QuestionDraft>(&format!(
+        "UPDATE generated_questions SET status = 'approved', question = $2, options = $3, \
+             correct_answer = $4, explanation = $5, law_category = $6, difficulty = $7, \
+             reviewed_by = $8, reviewed_at = NOW(), published_question_id = $9 \
+         WHERE id = $1 RETURNING {}",
+        DRAFT_COLUMNS
+    ))
+    .bind(id)
+    .bind(question.trim())
+    .bind(&options)
+    .bind(&correct_answer)
+    .bind(&explanation)
+    .bind(&law_category)
+    .bind(&difficulty)
+    .bind(reviewer)
+    .bind(published_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(approved)
+}
+
+/// Rejects a pending draft, keeping it with the moderator's note.
+pub async fn reject_draft(
+    db: &PgPool,
+    id: Uuid,
+    reviewer: Uuid,
+    note: Option<&str>,
+) -> Result<QuestionDraft, DraftError> {
+    let rejected = sqlx::query_as::<_, QuestionDraft>(&format!(
+        "UPDATE generated_questions SET status = 'rejected', review_note = $3, \
+             reviewed_by = $2, reviewed_at = NOW() \
+         WHERE id = $1 AND status = 'pending' RETURNING {}",
+        DRAFT_COLUMNS
+    ))
+    .bind(id)
+    .bind(reviewer)
+    .bind(note)
+    .fetch_optional(db)
+    .await?;
+
+    match rejected {
+        Some(draft) => Ok(draft),
+        None => {
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS (SELECT 1 FROM generated_questions WHERE id = $1)",
+            )
+            .bind(id)
+            .fetch_one(db)
+            .await?;
+            Err(if exists {
+                DraftError::Conflict("The question has already been moderated")
+            } else {
+                DraftError::NotFound
+            })
+        }
+    }
+}
+
+/// Drafts in one status, oldest first so the queue is worked in order.
+pub async fn list_drafts(
+    db: &PgPool,
+    status: &str,
+    limit: i64,
+) -> Result<Vec<QuestionDraft>, sqlx::Error> {
+    sqlx::query_as::<_, QuestionDraft>(&format!(
+        "SELECT {} FROM generated_questions WHERE status = $1 ORDER BY created_at LIMIT $2",
+        DRAFT_COLUMNS
+    ))
+    .bind(status)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
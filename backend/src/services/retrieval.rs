@@ -0,0 +1,159 @@
+use crate::services::ai::{AiService, EmbeddingTask};
+use pgvector::Vector;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+// Cosine distance beyond which a case is too far from the question to help.
+const MAX_CASE_DISTANCE: f64 = 0.6;
+const CASE_LIMIT: i64 = 5;
+const STATUTE_LIMIT: i64 = 3;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    Case,
+    Statute,
+}
+
+/// A case or statute from our own library that was given to the model as
+/// context for an answer.
+#[derive(Debug, Clone, Serialize)]
+pub struct Source {
+    pub id: Uuid,
+    pub kind: SourceKind,
+    pub title: String,
+    pub citation: Option<String>,
+    #[serde(skip)]
+    pub excerpt: String,
+}
+
+#[derive(FromRow)]
+struct SourceRow {
+    id: Uuid,
+    title: String,
+    citation: Option<String>,
+    excerpt: Option<String>,
+}
+
+impl SourceRow {
+    fn into_source(self, kind: SourceKind) -> Source {
+        Source {
+            id: self.id,
+            kind,
+            title: self.title,
+            citation: self.citation,
+            excerpt: self.excerpt.unwrap_or_default(),
+        }
+    }
+}
+
+/// Finds the cases and statutes most relevant to a question. Cases are
+/// matched by embedding first and topped up with full-text matches (the
+/// embedding worker may not have reached every case yet); statutes are
+/// matched by full-text only. Retrieval is best effort: failures are logged
+/// and simply yield fewer sources.
+pub async fn retrieve_sources(db: &PgPool, ai: &AiService, question: &str) -> Vec<Source> {
+    let mut sources: Vec<Source> = Vec::new();
+
+    match ai.embed_text(question, EmbeddingTask::Query).await {
+        Ok(values) => {
+            let rows = sqlx::query_as::<_, SourceRow>(
+                "SELECT id, title, citation, coalesce(summary, left(content, 1500)) AS excerpt \
+                 FROM cases WHERE embedding IS NOT NULL AND embedding <=> $1 < $2 \
+                 ORDER BY embedding <=> $1 LIMIT $3",
+            )
+            .bind(Vector::from(values))
+            .bind(MAX_CASE_DISTANCE)
+            .bind(CASE_LIMIT)
+            .fetch_all(db)
+            .await;
+
+            match rows {
+                Ok(rows) => {
+                    sources.extend(rows.into_iter().map(|r| r.into_source(SourceKind::Case)))
+                }
+                Err(e) => eprintln!("Semantic retrieval failed: {:?}", e),
+            }
+        }
+        Err(e) => eprintln!("Failed to embed question for retrieval: {}", e),
+    }
+
+    // A natural-language question rarely matches with every word ANDed
+    // together, so the full-text queries OR the question's lexemes instead.
+    let remaining = CASE_LIMIT - sources.len() as i64;
+    if remaining > 0 {
+        let rows = sqlx::query_as::<_, SourceRow>(
+            "SELECT id, title, citation, coalesce(summary, left(content, 1500)) AS excerpt \
+             FROM cases, replace(plainto_tsquery('english', $1)::text, '&', '|')::tsquery AS query \
+             WHERE search_vector @@ query \
+             ORDER BY ts_rank_cd(search_vector, query) DESC LIMIT $2",
+        )
+        .bind(question)
+        .bind(CASE_LIMIT)
+        .fetch_all(db)
+        .await;
+
+        match rows {
+            Ok(rows) => {
+                for row in rows {
+                    if sources.len() as i64 >= CASE_LIMIT {
+                        break;
+                    }
+                    if !sources.iter().any(|s| s.id == row.id) {
+                        sources.push(row.into_source(SourceKind::Case));
+                    }
+                }
+            }
+            Err(e) => eprintln!("Full-text case retrieval failed: {:?}", e),
+        }
+    }
+
+    let rows = sqlx::query_as::<_, SourceRow>(
+        "SELECT id, title, NULL::text AS citation, \
+                ts_headline('english', content, query, 'MaxFragments=3, MaxWords=60, MinWords=20') AS excerpt \
+         FROM statutes, replace(plainto_tsquery('english', $1)::text, '&', '|')::tsquery AS query \
+         WHERE search_vector @@ query \
+         ORDER BY ts_rank_cd(search_vector, query) DESC LIMIT $2",
+    )
+    .bind(question)
+    .bind(STATUTE_LIMIT)
+    .fetch_all(db)
+    .await;
+
+    match rows {
+        Ok(rows) => sources.extend(rows.into_iter().map(|r| r.into_source(SourceKind::Statute))),
+        Err(e) => eprintln!("Statute retrieval failed: {:?}", e),
+    }
+
+    sources
+}
+
+/// Renders retrieved sources as a numbered block for the prompt, so the
+/// model can refer back to them as [1], [2], ...
+pub fn format_sources(sources: &[Source]) -> String {
+    sources
+        .iter()
+        .enumerate()
+        .map(|(i, source)| {
+            let label = match source.kind {
+                SourceKind::Case => "Case",
+                SourceKind::Statute => "Statute",
+            };
+            let citation = source
+                .citation
+                .as_deref()
+                .map(|c| format!(", {}", c))
+                .unwrap_or_default();
+            format!(
+                "[{}] {}: {}{}\n{}",
+                i + 1,
+                label,
+                source.title,
+                citation,
+                source.excerpt.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
@@ -0,0 +1,321 @@
+use crate::models::exams::{AnswerKey, ExamQuestion};
+use crate::models::review::{DueItem, ReviewCase, ReviewItem};
+use sqlx::{PgExecutor, PgPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const MIN_EASE: f64 = 1.3;
+/// Quality at or above which a review counts as remembered.
+const PASSING_QUALITY: u8 = 3;
+/// Longest gap between reviews, so `due_on` stays a sensible date however
+/// often an item is remembered.
+const MAX_INTERVAL_DAYS: i32 = 365;
+
+const ITEM_COLUMNS: &str = "id, question_id, case_id, ease_factor, interval_days, repetitions, \
+     lapses, due_on, last_reviewed_at, created_at";
+
+/// An item's place in the SM-2 schedule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Schedule {
+    pub ease_factor: f64,
+    pub interval_days: i32,
+    pub repetitions: i32,
+}
+
+/// The SM-2 step. A review graded below 3 starts the item over with a
+/// one-day interval, keeping its ease. Otherwise the interval grows from 1
+/// to 6 days, then by the ease factor up to `MAX_INTERVAL_DAYS`, and the
+/// ease moves with the grade (never below 1.3).
+pub fn sm2(schedule: Schedule, quality: u8) -> Schedule {
+    let quality = quality.min(5);
+    if quality < PASSING_QUALITY {
+        return Schedule {
+            interval_days: 1,
+            repetitions: 0,
+            ..schedule
+        };
+    }
+
+    let repetitions = schedule.repetitions + 1;
+    let interval_days = match repetitions {
+        1 => 1,
+        2 => 6,
+        _ => (schedule.interval_days as f64 * schedule.ease_factor)
+            .round()
+            .min(MAX_INTERVAL_DAYS as f64) as i32,
+    };
+    let miss = (5 - quality) as f64;
+    let ease_factor = (schedule.ease_factor + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASE);
+
+    Schedule {
+        ease_factor,
+        interval_days,
+        repetitions,
+    }
+}
+
+/// Queues a question the user got wrong for review tomorrow. Missing a
+/// question already in the queue is a lapse: it starts over, keeping its
+/// ease.
+pub async fn enqueue_missed_question<'e>(
+    db: impl PgExecutor<'e>,
+    user_id: Uuid,
+    question_id: Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO review_items (user_id, question_id, interval_days, due_on) \
+         VALUES ($1, $2, 1, CURRENT_DATE + 1) \
+         ON CONFLICT (user_id, question_id) DO UPDATE SET \
+             repetitions = 0, interval_days = 1, due_on = CURRENT_DATE + 1, \
+             lapses = review_items.lapses + (review_items.repetitions > 0)::INTEGER",
+    )
+    .bind(user_id)
+    .bind(question_id)
+    .execute(db)
+    .await
+    .map(|_| ())
+}
+
+/// Flags a case for review, due today. Flagging it again keeps its
+/// schedule. Returns `None` when the case does not exist.
+pub async fn flag_case(
+    db: &PgPool,
+    user_id: Uuid,
+    case_id: Uuid,
+) -> Result<Option<ReviewItem>, sqlx::Error> {
+    sqlx::query_as::<_, ReviewItem>(&format!(
+        "WITH flagged AS ( \
+             INSERT INTO review_items (user_id, case_id) \
+             SELECT $1, id FROM cases WHERE id = $2 \
+             ON CONFLICT (user_id, case_id) DO NOTHING \
+             RETURNING {columns} \
+         ) \
+         SELECT {columns} FROM flagged \
+         UNION ALL \
+         SELECT {columns} FROM review_items WHERE user_id = $1 AND case_id = $2",
+        columns = ITEM_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(case_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// Records a review graded `quality` (0-5) and schedules the next one.
+/// Returns `None` when the item is not the user's.
+pub async fn record_review(
+    db: &PgPool,
+    user_id: Uuid,
+    item_id: Uuid,
+    quality: u8,
+) -> Result<Option<ReviewItem>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let Some(current) = sqlx::query_as::<_, ReviewItem>(&format!(
+        "SELECT {} FROM review_items WHERE id = $1 AND user_id = $2 FOR UPDATE",
+        ITEM_COLUMNS
+    ))
+    .bind(item_id)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let next = sm2(
+        Schedule {
+            ease_factor: current.ease_factor,
+            interval_days: current.interval_days,
+            repetitions: current.repetitions,
+        },
+        quality,
+    );
+    let lapsed = quality < PASSING_QUALITY && current.repetitions > 0;
+
+    let item = sqlx::query_as::<_, ReviewItem>(&format!(
+        "UPDATE review_items SET ease_factor = $2, interval_days = $3, repetitions = $4, \
+             lapses = lapses + $5, due_on = CURRENT_DATE + $3, last_reviewed_at = NOW() \
+         WHERE id = $1 RETURNING {}",
+        ITEM_COLUMNS
+    ))
+    .bind(item_id)
+    .bind(next.ease_factor)
+    .bind(next.interval_days)
+    .bind(next.repetitions)
+    .bind(lapsed as i32)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // user_activity rows need a profile; users without one just go unlogged.
+    sqlx::query(
+        "INSERT INTO user_activity (user_id, activity_type, target_id) \
+         SELECT id, 'review', $2 FROM profiles WHERE id = $1",
+    )
+    .bind(user_id)
+    .bind(item.question_id.or(item.case_id))
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(Some(item))
+}
+
+/// The user's review queue for today: items due today or overdue, most
+/// overdue and hardest first.
+pub async fn due_items(
+    db: &PgPool,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<DueItem>, sqlx::Error> {
+    let items = sqlx::query_as::<_, ReviewItem>(&format!(
+        "SELECT {} FROM review_items WHERE user_id = $1 AND due_on <= CURRENT_DATE \
+         ORDER BY due_on, ease_factor LIMIT $2",
+        ITEM_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+
+    let question_ids: Vec<Uuid> = items.iter().filter_map(|i| i.question_id).collect();
+    let case_ids: Vec<Uuid> = items.iter().filter_map(|i| i.case_id).collect();
+
+    let mut questions: HashMap<Uuid, ExamQuestion> = sqlx::query_as::<_, ExamQuestion>(
+        "SELECT id, question, question_type, options, law_category, difficulty, source, year, created_at \
+         FROM exam_questions WHERE id = ANY($1)",
+    )
+    .bind(&question_ids)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|q| (q.id, q))
+    .collect();
+
+    let mut cases: HashMap<Uuid, ReviewCase> = sqlx::query_as::<_, ReviewCase>(
+        "SELECT id, title, citation, court, year, summary, good_law_status \
+         FROM cases WHERE id = ANY($1)",
+    )
+    .bind(&case_ids)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|c| (c.id, c))
+    .collect();
+
+    Ok(items
+        .into_iter()
+        .map(|item| DueItem {
+            question: item.question_id.and_then(|id| questions.remove(&id)),
+            case: item.case_id.and_then(|id| cases.remove(&id)),
+            item,
+        })
+        .collect())
+}
+
+#[derive(sqlx::FromRow)]
+struct QuestionKeyRow {
+    question_id: Uuid,
+    #[sqlx(flatten)]
+    key: AnswerKey,
+}
+
+/// The answer key of a review item that is one of the user's questions.
+pub async fn question_key(
+    db: &PgPool,
+    user_id: Uuid,
+    item_id: Uuid,
+) -> Result<Option<(Uuid, AnswerKey)>, sqlx::Error> {
+    let row = sqlx::query_as::<_, QuestionKeyRow>(
+        "SELECT r.question_id, q.question_type, q.options, q.correct_answer, q.explanation \
+         FROM review_items r JOIN exam_questions q ON q.id = r.question_id \
+         WHERE r.id = $1 AND r.user_id = $2",
+    )
+    .bind(item_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|r| (r.question_id, r.key)))
+}
+
+/// Removes an item from the user's queue. Returns whether one was removed.
+pub async fn remove_item(db: &PgPool, user_id: Uuid, item_id: Uuid) -> Result<bool, sqlx::Error> {
+    sqlx::query("DELETE FROM review_items WHERE id = $1 AND user_id = $2")
+        .bind(item_id)
+        .bind(user_id)
+        .execute(db)
+        .await
+        .map(|r| r.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEW: Schedule = Schedule {
+        ease_factor: 2.5,
+        interval_days: 0,
+        repetitions: 0,
+    };
+
+    #[test]
+    fn follows_the_standard_sequence() {
+        let first = sm2(NEW, 4);
+        assert_eq!(first.interval_days, 1);
+        assert_eq!(first.repetitions, 1);
+        assert!((first.ease_factor - 2.5).abs() < 1e-9);
+
+        let second = sm2(first, 4);
+        assert_eq!(second.interval_days, 6);
+
+        let third = sm2(second, 4);
+        assert_eq!(third.interval_days, 15);
+        assert_eq!(third.repetitions, 3);
+    }
+
+    #[test]
+    fn ease_follows_the_grade() {
+        assert!((sm2(NEW, 5).ease_factor - 2.6).abs() < 1e-9);
+        assert!((sm2(NEW, 3).ease_factor - 2.36).abs() < 1e-9);
+        let hard = Schedule {
+            ease_factor: MIN_EASE,
+            ..NEW
+        };
+        assert!((sm2(hard, 3).ease_factor - MIN_EASE).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_lapse_starts_over_keeping_the_ease() {
+        let learned = Schedule {
+            ease_factor: 2.2,
+            interval_days: 40,
+            repetitions: 5,
+        };
+        assert_eq!(
+            sm2(learned, 2),
+            Schedule {
+                ease_factor: 2.2,
+                interval_days: 1,
+                repetitions: 0,
+            }
+        );
+        assert_eq!(sm2(learned, 0).interval_days, 1);
+    }
+
+    #[test]
+    fn caps_the_interval() {
+        let mut schedule = NEW;
+        for _ in 0..50 {
+            schedule = sm2(schedule, 5);
+            assert!(schedule.interval_days <= MAX_INTERVAL_DAYS);
+        }
+        assert_eq!(schedule.interval_days, MAX_INTERVAL_DAYS);
+        assert_eq!(schedule.repetitions, 50);
+    }
+
+    #[test]
+    fn treats_grades_above_five_as_five() {
+        assert_eq!(sm2(NEW, 9), sm2(NEW, 5));
+    }
+}
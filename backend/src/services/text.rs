@@ -0,0 +1,193 @@
+/// Splits long legal text into chunks of at most `max_chars` characters.
+///
+/// Chunks break on paragraph boundaries where possible, then on sentence
+/// boundaries, and only cut mid-sentence when a single sentence is longer
+/// than the limit.
+pub fn split_into_chunks(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        for piece in split_oversized(paragraph, max_chars) {
+            let needed = if current.is_empty() {
+                piece.chars().count()
+            } else {
+                current.chars().count() + 2 + piece.chars().count()
+            };
+
+            if needed > max_chars && !current.is_empty() {
+                chunks.push(std::mem::take(&mut current));
+            }
+
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn split_oversized(paragraph: &str, max_chars: usize) -> Vec<String> {
+    if paragraph.chars().count() <= max_chars {
+        return vec![paragraph.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for sentence in paragraph.split_inclusive(". ") {
+        if current.chars().count() + sentence.chars().count() > max_chars && !current.is_empty() {
+            pieces.push(std::mem::take(&mut current).trim().to_string());
+        }
+
+        if sentence.chars().count() > max_chars {
+            let chars: Vec<char> = sentence.chars().collect();
+            for window in chars.chunks(max_chars) {
+                pieces.push(window.iter().collect::<String>().trim().to_string());
+            }
+        } else {
+            current.push_str(sentence);
+        }
+    }
+
+    if !current.trim().is_empty() {
+        pieces.push(current.trim().to_string());
+    }
+
+    pieces
+}
+
+/// Rough token count for budgeting (about four characters per token for
+/// English prose). Good enough for quotas and prompt sizing, not billing.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// The first `max_chars` characters of `text`, cut on a character boundary.
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// The paragraph of `text` containing byte `offset`, cut to about
+/// `max_chars` characters around the offset when it is longer.
+pub fn paragraph_at(text: &str, offset: usize, max_chars: usize) -> String {
+    let start = text[..offset].rfind("\n\n").map_or(0, |i| i + 2);
+    let end = text[offset..]
+        .find("\n\n")
+        .map_or(text.len(), |i| offset + i);
+    let paragraph = &text[start..end];
+
+    if paragraph.chars().count() <= max_chars {
+        return paragraph.trim().to_string();
+    }
+
+    let before = text[start..offset].chars().count();
+    let skip = before.saturating_sub(max_chars / 2);
+    let excerpt: String = paragraph.chars().skip(skip).take(max_chars).collect();
+    format!("…{}…", excerpt.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitting_empty_text_gives_no_chunks() {
+        assert!(split_into_chunks("", 100).is_empty());
+        assert!(split_into_chunks("\n\n  \n\n", 100).is_empty());
+    }
+
+    #[test]
+    fn packs_paragraphs_up_to_the_limit() {
+        let text = "First paragraph.\n\nSecond paragraph.\n\nThird paragraph.";
+        assert_eq!(
+            split_into_chunks(text, 40),
+            ["First paragraph.\n\nSecond paragraph.", "Third paragraph."]
+        );
+        assert_eq!(split_into_chunks(text, 1_000), [text]);
+    }
+
+    #[test]
+    fn splits_a_long_paragraph_on_sentences() {
+        let paragraph = "The appeal is allowed. The conviction is set aside. \
+                         The appellant is discharged and acquitted.";
+        let chunks = split_into_chunks(paragraph, 55);
+        assert_eq!(
+            chunks,
+            [
+                "The appeal is allowed. The conviction is set aside.",
+                "The appellant is discharged and acquitted."
+            ]
+        );
+    }
+
+    #[test]
+    fn cuts_a_sentence_longer_than_the_limit() {
+        let sentence = "a".repeat(250);
+        let chunks = split_into_chunks(&sentence, 100);
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            [100, 100, 50]
+        );
+        assert_eq!(chunks.concat(), sentence);
+    }
+
+    #[test]
+    fn counts_characters_not_bytes() {
+        // Two bytes per "é" and three per "—": a byte-based cut would panic
+        // or overshoot the limit.
+        let paragraph = "Ọ́bá — é".repeat(40);
+        let chunks = split_into_chunks(&paragraph, 33);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 33));
+        assert_eq!(chunks.concat().replace(' ', ""), paragraph.replace(' ', ""));
+    }
+
+    #[test]
+    fn truncates_on_character_boundaries() {
+        assert_eq!(truncate_chars("Ọ́bá é", 3), "Ọ́b");
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("", 3), "");
+        assert_eq!(truncate_chars("abc", 0), "");
+    }
+
+    #[test]
+    fn finds_the_paragraph_around_an_offset() {
+        let text = "First paragraph.\n\nThe é—second paragraph.\n\nThird.";
+        let offset = text.find("second").unwrap();
+        assert_eq!(paragraph_at(text, offset, 100), "The é—second paragraph.");
+        assert_eq!(paragraph_at(text, 0, 100), "First paragraph.");
+        assert_eq!(paragraph_at(text, text.len() - 1, 100), "Third.");
+    }
+
+    #[test]
+    fn excerpts_a_long_paragraph_around_the_offset() {
+        let text = format!("{}target{}", "é ".repeat(200), " ü".repeat(200));
+        let offset = text.find("target").unwrap();
+        let excerpt = paragraph_at(&text, offset, 40);
+
+        assert!(excerpt.starts_with('…') && excerpt.ends_with('…'));
+        assert!(excerpt.contains("target"));
+        assert!(excerpt.chars().count() <= 42);
+    }
+
+    #[test]
+    fn paragraph_of_empty_text_is_empty() {
+        assert_eq!(paragraph_at("", 0, 100), "");
+    }
+
+    #[test]
+    fn estimates_four_characters_per_token() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("ééééé"), 2);
+    }
+}
@@ -1,112 +1,34 @@
 use crate::models::db::AppState;
-use reqwest::Client;
-use serde_json::json;
-use sqlx::Row;
+use crate::models::jobs::SummarizationJob;
+use crate::services::ai::GeneratedBrief;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Transaction};
 use std::time::Duration;
 use tokio::time::sleep;
+use uuid::Uuid;
 
-pub struct AiService {
-    client: Client,
-    api_key: String,
-}
-
-impl AiService {
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-            api_key: std::env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set"),
-        }
-    }
+// A running job whose worker has not reported back in this long is assumed
+// dead and handed to another worker, unless it has used all its attempts.
+const STALE_LOCK_MINUTES: i32 = 15;
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 6 * 60 * 60;
 
-    pub async fn summarize_case(&self, content: &str) -> Result<String, String> {
-        let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/gemini-3-flash-preview:generateContent?key={}",
-            self.api_key
-        );
-
-        let body = json!({
-            "contents": [{
-                "parts": [{
-                    "text": format!(
-                        "You are a legal AI assistant specialized in Nigerian and African law. \
-                        Summarize the following legal case clearly and concisely, \
-                        highlighting the key facts, legal issues, and outcome:\n\n{}",
-                        content
-                    )
-                }]
-            }]
-        });
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("Gemini API error: {} - {}", status, error_text));
-        }
-
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
-
-        let text = data["candidates"][0]["content"]["parts"][0]["text"]
-            .as_str()
-            .ok_or("No text in Gemini response")?
-            .to_string();
-
-        Ok(text)
-    }
-}
-
-pub async fn start_summarization_worker(state: AppState) {
-    println!("Starting AI Summarization Worker...");
-    let ai_service = AiService::new();
+/// Processes `summarization_jobs`. Jobs are claimed with `FOR UPDATE SKIP
+/// LOCKED`, so several workers (in this process or others) never pick the
+/// same case.
+pub async fn start_summarization_worker(state: AppState, worker: usize) {
+    println!("Starting AI Summarization Worker {}...", worker);
 
     loop {
-        let case_to_summarize = sqlx::query(
-            "SELECT id, content FROM cases WHERE summary IS NULL ORDER BY created_at ASC LIMIT 1",
-        )
-        .fetch_optional(&state.db)
-        .await;
-
-        match case_to_summarize {
-            Ok(Some(row)) => {
-                let id: uuid::Uuid = row.get("id");
-                let content: String = row.get("content");
-                println!("Worker found case to summarize: {}", id);
-
-                match ai_service.summarize_case(&content).await {
-                    Ok(summary) => {
-                        let update_result = sqlx::query(
-                            "UPDATE cases SET summary = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2"
-                        )
-                        .bind(summary)
-                        .bind(id)
-                        .execute(&state.db)
-                        .await;
-
-                        match update_result {
-                            Ok(_) => println!("Successfully summarized and saved case: {}", id),
-                            Err(e) => eprintln!("Failed to save summary for case {}: {}", id, e),
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("AI Summarization failed for case {}: {}", id, e);
-                        let _ = sqlx::query(
-                            "UPDATE cases SET summary = 'ERROR: Summarization failed', updated_at = CURRENT_TIMESTAMP WHERE id = $1"
-                        )
-                        .bind(id)
-                        .execute(&state.db)
-                        .await;
-                    }
+        match claim_job(&state.db).await {
+            Ok(Some(job)) => {
+                println!(
+                    "Worker {} claimed summarization job {} for case {} (attempt {} of {})",
+                    worker, job.id, job.case_id, job.attempts, job.max_attempts
+                );
+                let result = summarize(&state, job.case_id).await;
+                if let Err(e) = finish_job(&state.db, &job, result).await {
+                    eprintln!("Failed to update summarization job {}: {}", job.id, e);
                 }
             }
             Ok(None) => {
@@ -121,3 +43,182 @@ pub async fn start_summarization_worker(state: AppState) {
         sleep(Duration::from_secs(2)).await;
     }
 }
+
+async fn claim_job(db: &PgPool) -> Result<Option<SummarizationJob>, sqlx::Error> {
+    // A job whose worker died on its last attempt is parked where admins can
+    // see and requeue it.
+    sqlx::query(
+        "UPDATE summarization_jobs SET status = 'failed', locked_at = NULL, updated_at = NOW(), \
+             last_error = 'The worker stopped responding on the last attempt' \
+         WHERE status = 'running' AND locked_at < NOW() - make_interval(mins => $1) \
+           AND attempts >= max_attempts",
+    )
+    .bind(STALE_LOCK_MINUTES)
+    .execute(db)
+    .await?;
+
+    sqlx::query_as::<_, SummarizationJob>(
+        "UPDATE summarization_jobs SET status = 'running', attempts = attempts + 1, \
+             locked_at = NOW(), updated_at = NOW() \
+         WHERE id = ( \
+             SELECT id FROM summarization_jobs \
+             WHERE (status = 'pending' AND next_run_at <= NOW()) \
+                OR (status = 'running' AND locked_at < NOW() - make_interval(mins => $1) \
+                    AND attempts < max_attempts) \
+             ORDER BY next_run_at \
+             FOR UPDATE SKIP LOCKED \
+             LIMIT 1 \
+         ) \
+         RETURNING id, case_id, status, attempts, max_attempts, last_error, next_run_at, locked_at, created_at, updated_at",
+    )
+    .bind(STALE_LOCK_MINUTES)
+    .fetch_optional(db)
+    .await
+}
+
+// Why a job did not produce a brief. A fatal failure will not go away on
+// retry, so the job is parked straight away.
+enum Failure {
+    Retry(String),
+    Fatal(String),
+}
+
+// A brief ready to save. `summary` is only replaced when the brief was made
+// from the judgment itself; a summary-only case keeps its hand-written one.
+struct Briefed {
+    generated: GeneratedBrief,
+    replace_summary: bool,
+}
+
+async fn summarize(state: &AppState, case_id: Uuid) -> Result<Briefed, Failure> {
+    let (content, summary) = sqlx::query_as::<_, (Option<String>, Option<String>)>(
+        "SELECT content, summary FROM cases WHERE id = $1",
+    )
+    .bind(case_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| Failure::Retry(format!("Failed to load case: {}", e)))?;
+
+    let content = content.filter(|c| !c.trim().is_empty());
+    let replace_summary = content.is_some();
+    let Some(text) = content.or(summary.filter(|s| !s.trim().is_empty())) else {
+        return Err(Failure::Fatal(
+            "The case has neither content nor a summary to brief".to_string(),
+        ));
+    };
+
+    let generated = state
+        .ai
+        .brief_case(&text)
+        .await
+        .map_err(|e| Failure::Retry(format!("AI summarization failed: {}", e)))?;
+
+    Ok(Briefed {
+        generated,
+        replace_summary,
+    })
+}
+
+// Records the outcome, but only while this worker still holds the job: once
+// a stale lock is reclaimed, `locked_at` and `attempts` change and the late
+// result is dropped. A brief is saved in the same transaction as the job
+// update. Failures are retried with exponential backoff (30s, 1m, 2m, ...
+// capped at 6h) until the attempts run out.
+async fn finish_job(
+    db: &PgPool,
+    job: &SummarizationJob,
+    result: Result<Briefed, Failure>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let (status, error, backoff) = match &result {
+        Ok(_) => ("succeeded", None, 0),
+        Err(failure) => {
+            let (error, fatal) = match failure {
+                Failure::Retry(e) => (e, false),
+                Failure::Fatal(e) => (e, true),
+            };
+            eprintln!(
+                "Summarization job {} for case {} failed (attempt {} of {}): {}",
+                job.id, job.case_id, job.attempts, job.max_attempts, error
+            );
+            let exhausted = fatal || job.attempts >= job.max_attempts;
+            let backoff = BASE_BACKOFF_SECS
+                .saturating_mul(1i64 << (job.attempts - 1).clamp(0, 20))
+                .min(MAX_BACKOFF_SECS);
+            (
+                if exhausted { "failed" } else { "pending" },
+                Some(error.as_str()),
+                backoff,
+            )
+        }
+    };
+
+    let updated = sqlx::query(
+        "UPDATE summarization_jobs SET status = $2, last_error = $3, \
+             next_run_at = CASE WHEN $2 = 'pending' THEN NOW() + make_interval(secs => $4) ELSE next_run_at END, \
+             locked_at = NULL, updated_at = NOW() \
+         WHERE id = $1 AND status = 'running' AND locked_at = $5 AND attempts = $6",
+    )
+    .bind(job.id)
+    .bind(status)
+    .bind(error)
+    .bind(backoff as f64)
+    .bind(job.locked_at)
+    .bind(job.attempts)
+    .execute(&mut *tx)
+    .await?;
+    if updated.rows_affected() == 0 {
+        eprintln!(
+            "Summarization job {} lost its lock to another worker; dropping this result",
+            job.id
+        );
+        return tx.rollback().await;
+    }
+
+    if let Ok(briefed) = &result {
+        save_brief(&mut tx, job.case_id, briefed).await?;
+        println!("Successfully briefed and saved case: {}", job.case_id);
+    }
+
+    tx.commit().await
+}
+
+// Writes the brief and, when it was made from the judgment, the short
+// summary, so a case never has a generated summary without its brief.
+async fn save_brief(
+    tx: &mut Transaction<'_, Postgres>,
+    case_id: Uuid,
+    briefed: &Briefed,
+) -> Result<(), sqlx::Error> {
+    let brief = &briefed.generated.brief;
+
+    sqlx::query(
+        "INSERT INTO case_briefs (case_id, facts, issues, holding, ratio_decidendi, obiter_dicta, disposition, judges) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) \
+         ON CONFLICT (case_id) DO UPDATE SET facts = EXCLUDED.facts, issues = EXCLUDED.issues, \
+             holding = EXCLUDED.holding, ratio_decidendi = EXCLUDED.ratio_decidendi, \
+             obiter_dicta = EXCLUDED.obiter_dicta, disposition = EXCLUDED.disposition, \
+             judges = EXCLUDED.judges, updated_at = NOW()",
+    )
+    .bind(case_id)
+    .bind(&brief.facts)
+    .bind(&brief.issues)
+    .bind(&brief.holding)
+    .bind(&brief.ratio_decidendi)
+    .bind(&brief.obiter_dicta)
+    .bind(&brief.disposition)
+    .bind(Json(&brief.judges))
+    .execute(&mut **tx)
+    .await?;
+
+    if briefed.replace_summary {
+        sqlx::query("UPDATE cases SET summary = $1, updated_at = CURRENT_TIMESTAMP WHERE id = $2")
+            .bind(&briefed.generated.summary)
+            .bind(case_id)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
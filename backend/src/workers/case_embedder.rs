@@ -0,0 +1,120 @@
+use crate::models::db::AppState;
+use crate::services::ai::EmbeddingTask;
+use crate::services::embeddings::{EMBEDDING_DIMENSIONS, EmbeddingProvider, mean_pool};
+use crate::services::text::split_into_chunks;
+use pgvector::Vector;
+use sqlx::Row;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+// text-embedding-004 accepts ~2048 tokens; stay well under it.
+const CHUNK_CHARS: usize = 6000;
+// Caps the cost of embedding a single very long judgment.
+const MAX_CHUNKS: usize = 32;
+
+pub async fn start_embedding_worker(state: AppState, provider: Arc<dyn EmbeddingProvider>) {
+    println!("Starting Case Embedding Worker...");
+
+    loop {
+        // A case needs (re-)embedding when it has no vector yet or when its
+        // text has changed since: a trigger keeps embedding_source_hash up to
+        // date on every write. Failed attempts are retried at most once an hour.
+        let case_to_embed = sqlx::query(
+            "SELECT id, title, citation, summary, content, embedding_source_hash AS content_hash \
+             FROM cases \
+             WHERE (embedding IS NULL OR embedding_hash IS DISTINCT FROM embedding_source_hash) \
+               AND (embedding_attempted_at IS NULL \
+                    OR embedding_attempted_at < CURRENT_TIMESTAMP - INTERVAL '1 hour') \
+             ORDER BY embedding_attempted_at ASC NULLS FIRST, created_at ASC \
+             LIMIT 1",
+        )
+        .fetch_optional(&state.db)
+        .await;
+
+        match case_to_embed {
+            Ok(Some(row)) => {
+                let id: uuid::Uuid = row.get("id");
+                let title: String = row.get("title");
+                let citation: Option<String> = row.get("citation");
+                let summary: Option<String> = row.get("summary");
+                let content: Option<String> = row.get("content");
+                let content_hash: String = row.get("content_hash");
+                println!("Worker found case to embed: {}", id);
+
+                let _ = sqlx::query(
+                    "UPDATE cases SET embedding_attempted_at = CURRENT_TIMESTAMP WHERE id = $1",
+                )
+                .bind(id)
+                .execute(&state.db)
+                .await;
+
+                // Seeded cases often have only a summary, so fall back to it.
+                let body = content
+                    .filter(|c| !c.trim().is_empty())
+                    .or(summary)
+                    .unwrap_or_default();
+                let heading = format!("{} {}", title, citation.unwrap_or_default());
+
+                match embed_document(provider.as_ref(), heading.trim(), &body).await {
+                    Ok(embedding) => {
+                        let update_result = sqlx::query(
+                            "UPDATE cases SET embedding = $1, embedding_hash = $2 WHERE id = $3",
+                        )
+                        .bind(Vector::from(embedding))
+                        .bind(content_hash)
+                        .bind(id)
+                        .execute(&state.db)
+                        .await;
+
+                        match update_result {
+                            Ok(_) => println!("Successfully embedded case: {}", id),
+                            Err(e) => eprintln!("Failed to save embedding for case {}: {}", id, e),
+                        }
+                    }
+                    Err(e) => eprintln!("Embedding failed for case {}: {}", id, e),
+                }
+            }
+            Ok(None) => {
+                sleep(Duration::from_secs(30)).await;
+            }
+            Err(e) => {
+                eprintln!("Database error in embedding worker: {}", e);
+                sleep(Duration::from_secs(60)).await;
+            }
+        }
+
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+/// Embeds each chunk of the document with its heading prepended, then pools
+/// the chunk vectors into the single vector stored on the case.
+async fn embed_document(
+    provider: &dyn EmbeddingProvider,
+    heading: &str,
+    body: &str,
+) -> Result<Vec<f32>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut chunks = split_into_chunks(body, CHUNK_CHARS);
+    chunks.truncate(MAX_CHUNKS);
+    if chunks.is_empty() {
+        chunks.push(String::new());
+    }
+
+    let mut vectors = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        let text = format!("{}\n\n{}", heading, chunk);
+        let vector = provider.embed(text.trim(), EmbeddingTask::Document).await?;
+        if vector.len() != EMBEDDING_DIMENSIONS {
+            return Err(format!(
+                "Embedding has {} dimensions, expected {}",
+                vector.len(),
+                EMBEDDING_DIMENSIONS
+            )
+            .into());
+        }
+        vectors.push(vector);
+    }
+
+    mean_pool(&vectors).ok_or_else(|| "No embeddings to pool".into())
+}
@@ -0,0 +1,85 @@
+use crate::models::db::AppState;
+use crate::services::citation_graph::{
+    KnownCase, KnownStatute, extract_citations, replace_citations, resolve_pending,
+};
+use sqlx::Row;
+use sqlx::postgres::PgRow;
+use std::time::Duration;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+// Cases extracted per poll; the library is loaded once for each batch.
+const BATCH_SIZE: i64 = 20;
+
+/// Builds the citation graph: extracts the authorities each judgment cites
+/// into `case_citations`, and re-extracts whenever a judgment's text
+/// changes (tracked by `citations_hash`). A failed case is retried at most
+/// once an hour.
+pub async fn start_citation_worker(state: AppState) {
+    println!("Starting Citation Extraction Worker...");
+
+    loop {
+        match resolve_pending(&state.db).await {
+            Ok(0) => {}
+            Ok(n) => println!("Resolved {} pending citation(s)", n),
+            Err(e) => eprintln!("Failed to resolve pending citations: {}", e),
+        }
+
+        let cases_to_extract = sqlx::query(
+            "SELECT id, content, md5(content) AS content_hash FROM cases \
+             WHERE content IS NOT NULL AND citations_hash IS DISTINCT FROM md5(content) \
+               AND (citations_attempted_at IS NULL \
+                    OR citations_attempted_at < CURRENT_TIMESTAMP - INTERVAL '1 hour') \
+             ORDER BY citations_attempted_at ASC NULLS FIRST, created_at ASC \
+             LIMIT $1",
+        )
+        .bind(BATCH_SIZE)
+        .fetch_all(&state.db)
+        .await;
+
+        match cases_to_extract {
+            Ok(rows) if rows.is_empty() => {
+                sleep(Duration::from_secs(30)).await;
+            }
+            Ok(rows) => {
+                if let Err(e) = extract_batch(&state, &rows).await {
+                    eprintln!("Database error in citation worker: {}", e);
+                    sleep(Duration::from_secs(60)).await;
+                }
+            }
+            Err(e) => {
+                eprintln!("Database error in citation worker: {}", e);
+                sleep(Duration::from_secs(60)).await;
+            }
+        }
+
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+async fn extract_batch(state: &AppState, rows: &[PgRow]) -> Result<(), sqlx::Error> {
+    let cases = sqlx::query_as::<_, KnownCase>("SELECT id, title, citation_key FROM cases")
+        .fetch_all(&state.db)
+        .await?;
+    let statutes = sqlx::query_as::<_, KnownStatute>("SELECT id, title FROM statutes")
+        .fetch_all(&state.db)
+        .await?;
+
+    for row in rows {
+        let id: Uuid = row.get("id");
+        let content: String = row.get("content");
+        let content_hash: String = row.get("content_hash");
+
+        sqlx::query("UPDATE cases SET citations_attempted_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(id)
+            .execute(&state.db)
+            .await?;
+
+        let citations = extract_citations(id, &content, &cases, &statutes);
+        match replace_citations(&state.db, id, &content_hash, &citations).await {
+            Ok(()) => println!("Extracted {} citation(s) from case {}", citations.len(), id),
+            Err(e) => eprintln!("Citation extraction failed for case {}: {}", id, e),
+        }
+    }
+    Ok(())
+}
@@ -1 +1,5 @@
 pub mod ai_summarizer;
+pub mod case_embedder;
+pub mod citation_extractor;
+pub mod statute_indexer;
+pub mod treatment_classifier;
@@ -0,0 +1,38 @@
+use crate::models::db::AppState;
+use crate::services::provisions::{parse_provisions, replace_provisions};
+use sqlx::Row;
+use uuid::Uuid;
+
+/// Splits every statute that has no stored provisions yet. Runs once at
+/// startup; statutes whose text changes later are re-split through
+/// `POST /api/admin/statutes/{id}/provisions`.
+pub async fn index_statutes(state: AppState) {
+    let rows = match sqlx::query(
+        "SELECT id, content FROM statutes s \
+         WHERE NOT EXISTS (SELECT 1 FROM statute_provisions p WHERE p.statute_id = s.id)",
+    )
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Database error in statute indexer: {}", e);
+            return;
+        }
+    };
+
+    for row in rows {
+        let id: Uuid = row.get("id");
+        let content: String = row.get("content");
+
+        let provisions = parse_provisions(&content);
+        if provisions.is_empty() {
+            continue;
+        }
+
+        match replace_provisions(&state.db, id, &provisions).await {
+            Ok(count) => println!("Indexed {} provisions for statute {}", count, id),
+            Err(e) => eprintln!("Failed to index statute {}: {}", id, e),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::models::db::AppState;
+use sqlx::Row;
+use std::time::Duration;
+use tokio::time::sleep;
+use uuid::Uuid;
+
+/// Classifies how each citing judgment treats the case it cites (followed,
+/// distinguished, overruled, ...) from the citing paragraph. Failed
+/// classifications are retried at most once an hour; rows an admin has
+/// classified are never touched.
+pub async fn start_treatment_worker(state: AppState) {
+    println!("Starting Citation Treatment Worker...");
+
+    loop {
+        let citation_to_classify = sqlx::query(
+            "SELECT cc.id, cc.context, c.title, c.citation \
+             FROM case_citations cc \
+             JOIN cases c ON c.id = cc.cited_case_id \
+             WHERE cc.treatment IS NULL AND cc.context IS NOT NULL \
+               AND (cc.classification_attempted_at IS NULL \
+                    OR cc.classification_attempted_at < CURRENT_TIMESTAMP - INTERVAL '1 hour') \
+             ORDER BY cc.classification_attempted_at ASC NULLS FIRST, cc.created_at ASC \
+             LIMIT 1",
+        )
+        .fetch_optional(&state.db)
+        .await;
+
+        match citation_to_classify {
+            Ok(Some(row)) => {
+                let id: Uuid = row.get("id");
+                let context: String = row.get("context");
+                let title: String = row.get("title");
+                let citation: String = row.get("citation");
+
+                let _ = sqlx::query(
+                    "UPDATE case_citations SET classification_attempted_at = CURRENT_TIMESTAMP WHERE id = $1",
+                )
+                .bind(id)
+                .execute(&state.db)
+                .await;
+
+                let cited_case = format!("{} {}", title, citation);
+                match state.ai.classify_treatment(&cited_case, &context).await {
+                    Ok(classification) => {
+                        // The treatment IS NULL guard keeps a concurrent admin edit.
+                        let update_result = sqlx::query(
+                            "UPDATE case_citations SET treatment = $1, treatment_note = $2, \
+                                 classified_by = 'ai', classified_at = CURRENT_TIMESTAMP \
+                             WHERE id = $3 AND treatment IS NULL",
+                        )
+                        .bind(classification.treatment.as_str())
+                        .bind(&classification.note)
+                        .bind(id)
+                        .execute(&state.db)
+                        .await;
+
+                        match update_result {
+                            Ok(_) => println!(
+                                "Classified citation {} as {}",
+                                id,
+                                classification.treatment.as_str()
+                            ),
+                            Err(e) => {
+                                eprintln!("Failed to save treatment for citation {}: {}", id, e)
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Treatment classification failed for citation {}: {}", id, e)
+                    }
+                }
+            }
+            Ok(None) => {
+                sleep(Duration::from_secs(30)).await;
+            }
+            Err(e) => {
+                eprintln!("Database error in treatment worker: {}", e);
+                sleep(Duration::from_secs(60)).await;
+            }
+        }
+
+        sleep(Duration::from_secs(2)).await;
+    }
+}
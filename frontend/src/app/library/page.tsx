@@ -13,6 +13,13 @@ const MOCK_CASES = [
   { id: "4", title: "Awolowo v Federal Minister of Internal Affairs", year: 1962, court: "High Court", tags: ["Constitutional", "Rights"], excerpt: "Landmark case on the right to legal representation of one's choice under the Nigerian Constitution." }
 ];
 
+// Badge for cases.good_law_status; "unknown" gets none.
+const GOOD_LAW_BADGES: Record<string, { label: string; className: string }> = {
+  good_law: { label: "Good law", className: "bg-green-50 dark:bg-green-900/30 border-green-200 dark:border-green-800 text-green-600 dark:text-green-400" },
+  questioned: { label: "Questioned", className: "bg-amber-50 dark:bg-amber-900/30 border-amber-200 dark:border-amber-800 text-amber-600 dark:text-amber-400" },
+  overruled: { label: "Overruled", className: "bg-red-50 dark:bg-red-900/30 border-red-200 dark:border-red-800 text-red-600 dark:text-red-400" },
+};
+
 const API_URL = process.env.NEXT_PUBLIC_API_URL || "http://localhost:3000/api";
 
 export default function CaseLibrary() {
@@ -234,6 +241,11 @@ export default function CaseLibrary() {
                         {item.difficulty}
                       </span>
                     )}
+                    {GOOD_LAW_BADGES[item.good_law_status] && (
+                      <span className={`px-2.5 py-0.5 rounded-full text-xs font-medium border ${GOOD_LAW_BADGES[item.good_law_status].className}`}>
+                        {GOOD_LAW_BADGES[item.good_law_status].label}
+                      </span>
+                    )}
                   </div>
                 </div>
 
@@ -14,14 +14,14 @@ export default function ConstitutionPage() {
   useEffect(() => {
     const fetchConstitution = async () => {
       try {
-        // We'll fetch from the new statutes table (via a generic endpoint or specifically for constitution)
-        // For now, let's assume /api/statutes endpoint exists or we use a search
-        const response = await fetch(`${API_URL}/cases?topic=Constitutional Law`);
+        const response = await fetch(`${API_URL}/statutes?category=Constitution`);
         if (response.ok) {
           const data = await response.json();
-          // Find the one that looks like the Constitution
           const consti = data.find((d: any) => d.title.includes("Constitution"));
-          setConstitution(consti);
+          if (consti) {
+            const detail = await fetch(`${API_URL}/statutes/${consti.id}`);
+            setConstitution(detail.ok ? await detail.json() : consti);
+          }
         }
       } catch (error) {
         console.error("Failed to fetch constitution:", error);
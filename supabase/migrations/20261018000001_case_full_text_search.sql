@@ -0,0 +1,13 @@
+-- Full-text search over the case library
+-- Title and citation carry the most weight, then the summary, court and body text.
+
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(citation, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(summary, '')), 'B') ||
+        setweight(to_tsvector('english', coalesce(court, '')), 'C') ||
+        setweight(to_tsvector('english', coalesce(content, '')), 'D')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS cases_search_vector_idx ON cases USING gin (search_vector);
@@ -0,0 +1,6 @@
+-- Bookkeeping for the case embedding worker
+-- embedding_hash records which version of the case text the vector was built from,
+-- so edits to a case trigger a re-embed. embedding_attempted_at throttles retries.
+
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS embedding_hash TEXT;
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS embedding_attempted_at TIMESTAMP WITH TIME ZONE;
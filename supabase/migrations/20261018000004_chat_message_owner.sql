@@ -0,0 +1,7 @@
+-- Chat messages are attributed to the authenticated Supabase user.
+-- Depending on which earlier migration created chat_messages, user_id pointed at
+-- either profiles or auth.users; point it at auth.users so a message can be saved
+-- before the user's profile has been synced.
+ALTER TABLE chat_messages DROP CONSTRAINT IF EXISTS chat_messages_user_id_fkey;
+ALTER TABLE chat_messages ADD CONSTRAINT chat_messages_user_id_fkey
+    FOREIGN KEY (user_id) REFERENCES auth.users(id) ON DELETE CASCADE;
@@ -0,0 +1,9 @@
+-- Daily LEXA usage per caller, used to enforce request and token quotas.
+-- subject is 'user:<auth uid>' for signed-in users or 'ip:<address>' for guests.
+CREATE TABLE IF NOT EXISTS ai_usage_daily (
+    subject TEXT NOT NULL,
+    usage_date DATE NOT NULL DEFAULT CURRENT_DATE,
+    requests INTEGER NOT NULL DEFAULT 0,
+    tokens BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (subject, usage_date)
+);
@@ -0,0 +1,14 @@
+-- Structured IRAC briefs generated by the summarization worker, one per case
+CREATE TABLE IF NOT EXISTS case_briefs (
+    case_id UUID PRIMARY KEY REFERENCES cases(id) ON DELETE CASCADE,
+    facts TEXT NOT NULL,
+    issues TEXT[] NOT NULL DEFAULT '{}',
+    holding TEXT NOT NULL,
+    ratio_decidendi TEXT NOT NULL,
+    obiter_dicta TEXT[] NOT NULL DEFAULT '{}',
+    disposition TEXT NOT NULL,
+    -- [{"name": "...", "position": "lead" | "concurring" | "dissenting", "note": "..."}]
+    judges JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
@@ -0,0 +1,39 @@
+-- Queue for the summarization worker: retries with backoff, then parks the job as 'failed'
+CREATE TABLE IF NOT EXISTS summarization_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    case_id UUID NOT NULL UNIQUE REFERENCES cases(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'running', 'succeeded', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    last_error TEXT,
+    next_run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_summarization_jobs_due ON summarization_jobs(next_run_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_summarization_jobs_status ON summarization_jobs(status, updated_at DESC);
+
+-- Failed summaries used to be stored as this marker, which hid the case from the worker for good
+UPDATE cases SET summary = NULL WHERE summary = 'ERROR: Summarization failed';
+
+INSERT INTO summarization_jobs (case_id)
+SELECT id FROM cases WHERE summary IS NULL
+ON CONFLICT (case_id) DO NOTHING;
+
+-- Cases are added by seeds and the dashboard as well as the API, so enqueue in the database
+CREATE OR REPLACE FUNCTION enqueue_summarization_job() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO summarization_jobs (case_id) VALUES (NEW.id)
+    ON CONFLICT (case_id) DO NOTHING;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS cases_enqueue_summarization ON cases;
+CREATE TRIGGER cases_enqueue_summarization
+    AFTER INSERT ON cases
+    FOR EACH ROW
+    WHEN (NEW.summary IS NULL)
+    EXECUTE FUNCTION enqueue_summarization_job();
@@ -0,0 +1,9 @@
+-- Full-text search over statutes, mirroring cases.search_vector
+ALTER TABLE statutes ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(content, '')), 'D')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS statutes_search_vector_idx ON statutes USING gin (search_vector);
+CREATE INDEX IF NOT EXISTS idx_statutes_category ON statutes(lower(category));
@@ -0,0 +1,19 @@
+-- Statutes split into their parts, chapters, sections, subsections and paragraphs.
+-- Sections and everything under them get a stable `path` such as '36/6/c' (Section 36(6)(c)).
+CREATE TABLE IF NOT EXISTS statute_provisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    statute_id UUID NOT NULL REFERENCES statutes(id) ON DELETE CASCADE,
+    parent_id UUID REFERENCES statute_provisions(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('part', 'chapter', 'section', 'subsection', 'paragraph')),
+    number TEXT NOT NULL,
+    heading TEXT,
+    text TEXT NOT NULL DEFAULT '',
+    path TEXT,
+    -- Order of the provision in the enactment
+    position INTEGER NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (statute_id, path)
+);
+
+CREATE INDEX IF NOT EXISTS idx_statute_provisions_statute ON statute_provisions(statute_id, position);
+CREATE INDEX IF NOT EXISTS idx_statute_provisions_parent ON statute_provisions(parent_id);
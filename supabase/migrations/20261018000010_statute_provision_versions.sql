@@ -0,0 +1,26 @@
+-- Point-in-time text for statute provisions.
+-- A provision with no versions has had the same text since enactment (statute_provisions.text).
+-- Once amended, every text it has had is a version, and the version whose
+-- [effective_from, effective_to) range covers a date is the law on that date.
+CREATE TABLE IF NOT EXISTS statute_provision_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    provision_id UUID NOT NULL REFERENCES statute_provisions(id) ON DELETE CASCADE,
+    heading TEXT,
+    text TEXT NOT NULL,
+    -- NULL: in force since the statute was enacted
+    effective_from DATE,
+    -- NULL: still in force
+    effective_to DATE,
+    -- The instrument that brought this text in, e.g. 'Constitution (Fourth Alteration, No. 16) Act 2017'
+    amended_by TEXT,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK (effective_from IS NULL OR effective_to IS NULL OR effective_to > effective_from)
+);
+
+CREATE INDEX IF NOT EXISTS idx_statute_provision_versions_provision
+    ON statute_provision_versions(provision_id, effective_from);
+
+-- When a provision itself was inserted or repealed
+ALTER TABLE statute_provisions ADD COLUMN IF NOT EXISTS effective_from DATE;
+ALTER TABLE statute_provisions ADD COLUMN IF NOT EXISTS effective_to DATE;
+ALTER TABLE statute_provisions ADD COLUMN IF NOT EXISTS repealed_by TEXT;
@@ -0,0 +1,51 @@
+-- Citations compared on their runs of letters and digits, so "(Pt.97)" and "(Pt. 97)" are the
+-- same report, while "(Pt. 123) 45" and "(Pt. 12) 345" stay apart: "2001|1|nwlr|pt|123|45"
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS citation_key TEXT
+    GENERATED ALWAYS AS (
+        btrim(regexp_replace(
+            regexp_replace(
+                regexp_replace(lower(citation), '([a-z])([0-9])', '\1|\2', 'g'),
+                '([0-9])([a-z])', '\1|\2', 'g'),
+            '[^a-z0-9]+', '|', 'g'), '|')
+    ) STORED;
+
+-- Canonical spacing: "(Pt. 97)" and single spaces
+UPDATE cases
+SET citation = regexp_replace(
+        regexp_replace(citation, '\(\s*Pt\.?\s*(\d+)\s*\)', '(Pt. \1)', 'gi'),
+        '\s+', ' ', 'g')
+WHERE citation ~* '\(\s*Pt\.?\s*\d+\s*\)' OR citation ~ '\s{2,}';
+
+-- Some reports were seeded twice with different spacing. Copies under the same title are the
+-- same case: keep the earliest, whose brief and summarization job survive. Any other rows
+-- sharing a citation are left alone and reported, and the citation stays unenforced until an
+-- admin resolves them.
+DELETE FROM cases c
+USING cases d
+WHERE c.citation_key = d.citation_key
+  AND lower(regexp_replace(c.title, '[^A-Za-z0-9]', '', 'g'))
+    = lower(regexp_replace(d.title, '[^A-Za-z0-9]', '', 'g'))
+  AND (coalesce(c.created_at, 'epoch'), c.id) > (coalesce(d.created_at, 'epoch'), d.id);
+
+DO $$
+DECLARE
+    collision RECORD;
+    collisions INTEGER := 0;
+BEGIN
+    FOR collision IN
+        SELECT citation_key, string_agg(format('%s (%s)', title, id), '; ' ORDER BY created_at) AS cases
+        FROM cases
+        GROUP BY citation_key
+        HAVING count(*) > 1
+    LOOP
+        collisions := collisions + 1;
+        RAISE WARNING 'Cases share the citation key %: %', collision.citation_key, collision.cases;
+    END LOOP;
+
+    IF collisions = 0 THEN
+        CREATE UNIQUE INDEX IF NOT EXISTS cases_citation_key_idx ON cases(citation_key);
+    ELSE
+        RAISE WARNING '% citation(s) are shared by different cases; cases_citation_key_idx is not unique until they are resolved', collisions;
+        CREATE INDEX IF NOT EXISTS cases_citation_key_idx ON cases(citation_key);
+    END IF;
+END $$;
@@ -0,0 +1,31 @@
+-- Links from a judgment to the cases and statutes it cites, found by the
+-- citation worker. A reported citation to a case not yet in the library is
+-- kept with only cited_key set, and resolved once that case is added.
+
+CREATE TABLE IF NOT EXISTS case_citations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    citing_case_id UUID NOT NULL REFERENCES cases(id) ON DELETE CASCADE,
+    cited_case_id UUID REFERENCES cases(id) ON DELETE SET NULL,
+    cited_statute_id UUID REFERENCES statutes(id) ON DELETE CASCADE,
+    -- The citation or name as written in the judgment
+    cited_text TEXT NOT NULL,
+    -- cases.citation_key of a reported citation
+    cited_key TEXT,
+    -- The paragraph the citation appears in
+    context TEXT,
+    -- Order of first mention within the judgment
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    CHECK (num_nonnulls(cited_case_id, cited_statute_id) <= 1),
+    CHECK (cited_case_id IS NOT NULL OR cited_statute_id IS NOT NULL OR cited_key IS NOT NULL)
+);
+
+CREATE INDEX IF NOT EXISTS case_citations_citing_idx ON case_citations(citing_case_id);
+CREATE INDEX IF NOT EXISTS case_citations_cited_case_idx ON case_citations(cited_case_id);
+CREATE INDEX IF NOT EXISTS case_citations_cited_statute_idx ON case_citations(cited_statute_id);
+CREATE INDEX IF NOT EXISTS case_citations_unresolved_idx ON case_citations(cited_key)
+    WHERE cited_case_id IS NULL AND cited_statute_id IS NULL;
+
+-- Which version of the judgment the links were extracted from, so edits
+-- trigger a fresh pass (the same scheme as embedding_hash).
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS citations_hash TEXT;
@@ -0,0 +1,73 @@
+-- How a citing judgment treated the case it cites, and the resulting
+-- "good law" status of each case.
+
+ALTER TABLE case_citations ADD COLUMN IF NOT EXISTS treatment TEXT
+    CHECK (treatment IN ('cited', 'followed', 'applied', 'distinguished', 'doubted', 'overruled'));
+ALTER TABLE case_citations ADD COLUMN IF NOT EXISTS treatment_note TEXT;
+-- 'ai' for the classifier, 'admin' for a manual correction the classifier leaves alone
+ALTER TABLE case_citations ADD COLUMN IF NOT EXISTS classified_by TEXT
+    CHECK (classified_by IN ('ai', 'admin'));
+ALTER TABLE case_citations ADD COLUMN IF NOT EXISTS classified_at TIMESTAMP WITH TIME ZONE;
+-- Throttles retries after a failed classification
+ALTER TABLE case_citations ADD COLUMN IF NOT EXISTS classification_attempted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS case_citations_unclassified_idx ON case_citations(classification_attempted_at)
+    WHERE treatment IS NULL AND cited_case_id IS NOT NULL;
+
+-- 'overruled' if a later case overruled it, 'questioned' if one doubted it,
+-- 'good_law' if it has been followed, applied or distinguished, otherwise 'unknown'.
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS good_law_status TEXT NOT NULL DEFAULT 'unknown'
+    CHECK (good_law_status IN ('good_law', 'questioned', 'overruled', 'unknown'));
+
+-- Rough place of a court in the hierarchy; NULL when it cannot bind (a persuasive foreign
+-- court) or is not recognised.
+CREATE OR REPLACE FUNCTION court_rank(court TEXT) RETURNS INTEGER AS $$
+    SELECT CASE
+        WHEN court ILIKE '%persuasive%' THEN NULL
+        WHEN court ILIKE '%supreme court%' OR court ILIKE '%house of lords%'
+            OR court ILIKE '%privy council%' THEN 3
+        WHEN court ILIKE '%court of appeal%' THEN 2
+        WHEN court ILIKE '%high court%' OR court ILIKE '%bench%' OR court ILIKE '%division%'
+            OR court ILIKE '%chancery%' OR court ILIKE '%exchequer%' THEN 1
+    END
+$$ LANGUAGE sql IMMUTABLE;
+
+-- The classifier can be wrong, so its "overruled" only counts when a later decision of a
+-- higher court in the same jurisdiction made it. Anything else needs an admin's treatment.
+CREATE OR REPLACE FUNCTION refresh_good_law_status(target UUID) RETURNS void AS $$
+    UPDATE cases c SET good_law_status = (
+        SELECT CASE
+            WHEN bool_or(cc.treatment = 'overruled' AND (
+                    cc.classified_by = 'admin'
+                    OR (citing.year > c.year
+                        AND court_rank(citing.court) > court_rank(c.court)
+                        AND citing.jurisdiction IS NOT DISTINCT FROM c.jurisdiction)))
+                THEN 'overruled'
+            WHEN bool_or(cc.treatment = 'doubted') THEN 'questioned'
+            WHEN bool_or(cc.treatment IN ('followed', 'applied', 'distinguished')) THEN 'good_law'
+            ELSE 'unknown'
+        END
+        FROM case_citations cc JOIN cases citing ON citing.id = cc.citing_case_id
+        WHERE cc.cited_case_id = target
+    )
+    WHERE c.id = target;
+$$ LANGUAGE sql;
+
+CREATE OR REPLACE FUNCTION case_citations_refresh_good_law() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND OLD.cited_case_id IS NOT NULL THEN
+        PERFORM refresh_good_law_status(OLD.cited_case_id);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NEW.cited_case_id IS NOT NULL
+       AND NEW.cited_case_id IS DISTINCT FROM OLD.cited_case_id THEN
+        PERFORM refresh_good_law_status(NEW.cited_case_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS case_citations_good_law ON case_citations;
+CREATE TRIGGER case_citations_good_law
+    AFTER INSERT OR UPDATE OF cited_case_id, treatment, classified_by OR DELETE ON case_citations
+    FOR EACH ROW
+    EXECUTE FUNCTION case_citations_refresh_good_law();
@@ -0,0 +1,4 @@
+-- Filters used by GET /api/exams: category, source (Bar Finals, LLB), difficulty and year
+CREATE INDEX IF NOT EXISTS exam_questions_category_idx ON exam_questions (lower(law_category));
+CREATE INDEX IF NOT EXISTS exam_questions_source_idx ON exam_questions (lower(source));
+CREATE INDEX IF NOT EXISTS exam_questions_year_idx ON exam_questions (year);
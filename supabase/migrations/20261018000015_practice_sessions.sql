@@ -0,0 +1,26 @@
+-- Timed practice exams over exam_questions, with each answer kept for review
+CREATE TABLE IF NOT EXISTS practice_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'in_progress' CHECK (status IN ('in_progress', 'completed')),
+    time_limit_secs INTEGER CHECK (time_limit_secs > 0),
+    -- started_at + time_limit_secs; answers after this are refused
+    expires_at TIMESTAMPTZ,
+    score INTEGER,
+    total INTEGER NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_practice_sessions_user_id ON practice_sessions(user_id, started_at DESC);
+
+CREATE TABLE IF NOT EXISTS practice_answers (
+    session_id UUID NOT NULL REFERENCES practice_sessions(id) ON DELETE CASCADE,
+    question_id UUID NOT NULL REFERENCES exam_questions(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    answer TEXT,
+    -- Set when the session is graded
+    correct BOOLEAN,
+    answered_at TIMESTAMPTZ,
+    PRIMARY KEY (session_id, question_id)
+);
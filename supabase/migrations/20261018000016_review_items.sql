@@ -0,0 +1,20 @@
+-- Per-user spaced-repetition queue (SM-2) of missed exam questions and flagged cases
+CREATE TABLE IF NOT EXISTS review_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    question_id UUID REFERENCES exam_questions(id) ON DELETE CASCADE,
+    case_id UUID REFERENCES cases(id) ON DELETE CASCADE,
+    ease_factor DOUBLE PRECISION NOT NULL DEFAULT 2.5 CHECK (ease_factor >= 1.3),
+    interval_days INTEGER NOT NULL DEFAULT 0,
+    -- Successful reviews in a row; reset by a lapse
+    repetitions INTEGER NOT NULL DEFAULT 0,
+    lapses INTEGER NOT NULL DEFAULT 0,
+    due_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    last_reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (num_nonnulls(question_id, case_id) = 1),
+    UNIQUE (user_id, question_id),
+    UNIQUE (user_id, case_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_items_due ON review_items(user_id, due_on);
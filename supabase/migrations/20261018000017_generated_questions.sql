@@ -0,0 +1,27 @@
+-- Essay (problem) questions alongside multiple choice. Essays have no options;
+-- correct_answer holds the model answer.
+ALTER TABLE exam_questions ADD COLUMN IF NOT EXISTS question_type TEXT NOT NULL DEFAULT 'multiple_choice'
+    CHECK (question_type IN ('multiple_choice', 'essay'));
+
+-- Questions drafted by the AI from a case or a course topic. They wait here
+-- until a moderator approves them into exam_questions or rejects them.
+CREATE TABLE IF NOT EXISTS generated_questions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'approved', 'rejected')),
+    question_type TEXT NOT NULL CHECK (question_type IN ('multiple_choice', 'essay')),
+    question TEXT NOT NULL,
+    options JSONB,
+    correct_answer TEXT NOT NULL,
+    explanation TEXT,
+    law_category TEXT NOT NULL,
+    difficulty TEXT,
+    source_case_id UUID REFERENCES cases(id) ON DELETE SET NULL,
+    source_topic_id UUID REFERENCES topics(id) ON DELETE SET NULL,
+    reviewed_by UUID REFERENCES auth.users(id) ON DELETE SET NULL,
+    review_note TEXT,
+    reviewed_at TIMESTAMPTZ,
+    published_question_id UUID REFERENCES exam_questions(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_generated_questions_status ON generated_questions(status, created_at);
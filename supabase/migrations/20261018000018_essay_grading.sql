@@ -0,0 +1,21 @@
+-- Marking rubric of an essay question, as JSON:
+-- { "issues": [{ "issue": "...", "marks": 4 }],
+--   "authorities": [{ "name": "Donoghue v Stevenson", "citation": "[1932] AC 562", "marks": 2 }],
+--   "conclusion": { "conclusion": "...", "marks": 2 } }
+ALTER TABLE exam_questions ADD COLUMN IF NOT EXISTS rubric JSONB;
+
+-- Essay answers and how they were marked against the rubric. A student can
+-- answer the same question as often as they like.
+CREATE TABLE IF NOT EXISTS essay_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES auth.users(id) ON DELETE CASCADE,
+    question_id UUID NOT NULL REFERENCES exam_questions(id) ON DELETE CASCADE,
+    answer TEXT NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    max_score INTEGER NOT NULL,
+    marking JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_essay_attempts_user_question
+    ON essay_attempts(user_id, question_id, created_at DESC);
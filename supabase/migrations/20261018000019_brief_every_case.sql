@@ -0,0 +1,30 @@
+-- Every case gets a structured brief, including the seeded cases that
+-- already carry a hand-written summary.
+INSERT INTO summarization_jobs (case_id)
+SELECT c.id FROM cases c
+WHERE NOT EXISTS (SELECT 1 FROM case_briefs b WHERE b.case_id = c.id)
+ON CONFLICT (case_id) DO NOTHING;
+
+-- A new case is always queued, and a case whose text changes is briefed
+-- again from scratch.
+CREATE OR REPLACE FUNCTION enqueue_summarization_job() RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO summarization_jobs (case_id) VALUES (NEW.id)
+    ON CONFLICT (case_id) DO UPDATE SET status = 'pending', attempts = 0, last_error = NULL,
+        next_run_at = NOW(), locked_at = NULL, updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS cases_enqueue_summarization ON cases;
+CREATE TRIGGER cases_enqueue_summarization
+    AFTER INSERT ON cases
+    FOR EACH ROW
+    EXECUTE FUNCTION enqueue_summarization_job();
+
+DROP TRIGGER IF EXISTS cases_reenqueue_summarization ON cases;
+CREATE TRIGGER cases_reenqueue_summarization
+    AFTER UPDATE OF content ON cases
+    FOR EACH ROW
+    WHEN (OLD.content IS DISTINCT FROM NEW.content)
+    EXECUTE FUNCTION enqueue_summarization_job();
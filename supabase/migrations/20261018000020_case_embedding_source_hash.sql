@@ -0,0 +1,24 @@
+-- The embedding worker compares embedding_hash with this instead of hashing every
+-- case's full text on each poll. Same formula as before, so existing vectors stay current.
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS embedding_source_hash TEXT;
+
+CREATE OR REPLACE FUNCTION set_embedding_source_hash() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.embedding_source_hash := md5(concat_ws('|', NEW.title, NEW.citation, NEW.summary, NEW.content));
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS cases_embedding_source_hash ON cases;
+CREATE TRIGGER cases_embedding_source_hash
+    BEFORE INSERT OR UPDATE OF title, citation, summary, content ON cases
+    FOR EACH ROW
+    EXECUTE FUNCTION set_embedding_source_hash();
+
+UPDATE cases SET embedding_source_hash = md5(concat_ws('|', title, citation, summary, content))
+WHERE embedding_source_hash IS NULL;
+
+-- Only the cases still waiting for a vector, so the worker's poll stays cheap.
+CREATE INDEX IF NOT EXISTS cases_embedding_pending_idx
+    ON cases (embedding_attempted_at NULLS FIRST, created_at)
+    WHERE embedding IS NULL OR embedding_hash IS DISTINCT FROM embedding_source_hash;
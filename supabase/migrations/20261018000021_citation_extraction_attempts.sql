@@ -0,0 +1,2 @@
+-- Throttles the citation worker's retries of a case whose extraction failed
+ALTER TABLE cases ADD COLUMN IF NOT EXISTS citations_attempted_at TIMESTAMP WITH TIME ZONE;